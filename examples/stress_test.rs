@@ -8,6 +8,7 @@ use std::thread;
 use std::time::Duration;
 use std::time::Instant;
 use vsl_cli::commands::Commands;
+use vsl_cli::commands::SignOnlyArgs;
 use vsl_cli::configs::CliMode;
 use vsl_cli::configs::Config;
 use vsl_cli::configs::Configs;
@@ -15,6 +16,8 @@ use vsl_cli::configs::RpcServerInit;
 use vsl_cli::configs::RpcServerLocal;
 use vsl_cli::configs::VSL_TMP_CONFIG;
 use vsl_cli::execute::execute_command;
+use vsl_cli::inputs::DEFAULT_MAX_INPUT_SIZE;
+use vsl_cli::inputs::Encoding;
 use vsl_cli::networks::Network;
 use vsl_cli::rpc_client::RpcClient;
 use vsl_cli::rpc_client::check_network_is_up;
@@ -76,7 +79,10 @@ fn create_accounts(
         private_key: Some(
             "0xb6dd863bea551b5bb27ce9917316a01ea4c331f24e0e4fe56e28eb430f175ed7".to_string(),
         ),
+        mnemonic: None,
+        index: 0,
         overwrite: false,
+        unencrypted: true,
     };
     let load_master_response = execute_single_request(config, &load_master_account, &mut client);
     if !load_master_response.success {
@@ -94,6 +100,8 @@ fn create_accounts(
         let create_account_comm = Commands::AccountCreate {
             name: acc_name.clone(),
             overwrite: true,
+            unencrypted: true,
+            mnemonic: false,
         };
         let create_acc_response = execute_single_request(config, &create_account_comm, &mut client);
         let address = config
@@ -108,6 +116,7 @@ fn create_accounts(
                         network: None,
                         to: address.clone(),
                         amount: "0x1000".to_string(),
+                        signing: SignOnlyArgs::default(),
                     };
                     config.use_account("master");
                     let supply_account_response =
@@ -117,6 +126,7 @@ fn create_accounts(
                         let balance_comm = Commands::AccountBalance {
                             network: None,
                             account: None,
+                            raw: false,
                         };
                         let balance_response =
                             execute_single_request(config, &balance_comm, &mut client);
@@ -132,6 +142,7 @@ fn create_accounts(
                         let master_balance_comm = Commands::AccountBalance {
                             network: None,
                             account: Some("master".to_string()),
+                            raw: false,
                         };
                         let master_balance_response =
                             execute_single_request(config, &master_balance_comm, &mut client);
@@ -214,8 +225,12 @@ fn run_one_thread(
                 claim_type: "identity".to_string(),
                 proof: format!("proof_{}", i),
                 expires: None,
-                lifetime: Some(3600),
+                lifetime: Some("1h".to_string()),
                 fee: "0x1".to_string(),
+                claim_encoding: Encoding::Raw,
+                proof_encoding: Encoding::Raw,
+                max_size: DEFAULT_MAX_INPUT_SIZE,
+                signing: SignOnlyArgs::default(),
             };
             let submit_response = execute_single_request(&mut config, &submit_command, &mut client);
             results.push(submit_response.clone());
@@ -226,7 +241,8 @@ fn run_one_thread(
                             network: None,
                             address: None,
                             since: None,
-                            within: Some(3600),
+                            within: Some("1h".to_string()),
+                            raw: false,
                         };
                         let submitted_response =
                             execute_single_request(&mut config, &submitted_command, &mut client);
@@ -236,6 +252,7 @@ fn run_one_thread(
                                 network: None,
                                 claim: format!("test_claim_{}", i), //format!("{}", claim_id.clone()),
                                 address: None,
+                                signing: SignOnlyArgs::default(),
                             };
                             let settle_response =
                                 execute_single_request(&mut config, &settle_command, &mut client);
@@ -245,7 +262,8 @@ fn run_one_thread(
                                     network: None,
                                     address: None,
                                     since: None,
-                                    within: Some(3600),
+                                    within: Some("1h".to_string()),
+                                    raw: false,
                                 };
                                 let settled_response = execute_single_request(
                                     &mut config,
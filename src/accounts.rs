@@ -1,13 +1,27 @@
 #![allow(unused)]
 
+use crate::keystore::decrypt_keystore;
+use crate::keystore::encrypt_private_key;
+use crate::keystore::read_password;
+use crate::signers::AccountSigner;
+use crate::signers::ExternalSigner;
+use crate::signers::RemoteSigner;
+
 use alloy::hex::FromHex as _;
+use alloy::primitives::Address;
+use alloy::primitives::keccak256;
 use alloy::signers::k256::SecretKey;
+use alloy::signers::local::MnemonicBuilder;
 use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::local::coins_bip39::English;
+use alloy::signers::local::coins_bip39::Mnemonic;
 use anyhow::Result;
 use rand::thread_rng;
 use serde::Deserialize;
 use serde::Serialize;
+use serde_json::Value;
 use std::collections::HashMap;
+use std::str::FromStr as _;
 
 /// The primary data of an account: a private key and
 /// corresponding address.
@@ -15,11 +29,151 @@ use std::collections::HashMap;
 pub struct Credentials {
     /// The VSL address of a client, corresponding to the private key.
     pub address: String,
-    /// The private part of an account
+    /// The private part of an account, for the accounts which are not encrypted.
     /// WARNING !!! PRIVATE KEYS ARE STORED AS IS !!! ONLY FOR THE DEVNET PURPOSES !!!
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub private_key: String,
+    /// The private key, encrypted with a password in the Web3 Secret Storage format.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keystore: Option<Value>,
+    /// The command line of an external signer process, which holds the private key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external: Option<Vec<String>>,
+    /// The URL of a remote signing service, which holds the private key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+}
+
+impl Credentials {
+    /// The kind of the account signer: `plain`, `encrypted`, `external`, `remote` or `watch-only`.
+    pub fn kind(&self) -> &'static str {
+        if self.is_watch_only() {
+            "watch-only"
+        } else if self.external.is_some() {
+            "external"
+        } else if self.remote.is_some() {
+            "remote"
+        } else if self.is_encrypted() {
+            "encrypted"
+        } else {
+            "plain"
+        }
+    }
+    /// Checks if the account has no private key at all, so it may be used only for reading.
+    pub fn is_watch_only(&self) -> bool {
+        self.private_key.is_empty()
+            && self.keystore.is_none()
+            && self.external.is_none()
+            && self.remote.is_none()
+    }
+    /// Checks if the signing is delegated to an external signer process or a remote signing service.
+    pub fn is_external(&self) -> bool {
+        self.external.is_some() || self.remote.is_some()
+    }
+    /// Checks if the private key is stored encrypted.
+    pub fn is_encrypted(&self) -> bool {
+        self.keystore.is_some()
+    }
+    /// Encrypts the private key with a password and erases its plain copy.
+    pub fn encrypt(&mut self, password: &str) -> Result<()> {
+        if self.is_external() {
+            return Err(anyhow::anyhow!(
+                "The private key of '{}' is held by an external signer",
+                self.address
+            ));
+        }
+        if self.is_encrypted() {
+            return Err(anyhow::anyhow!(
+                "The private key of '{}' is already encrypted",
+                self.address
+            ));
+        }
+        self.keystore = Some(encrypt_private_key(&self.private_key, password)?);
+        self.private_key = String::new();
+        Ok(())
+    }
+    /// Returns the V3 keystore of the account. The unencrypted private key is encrypted with `password`.
+    pub fn export_keystore(&self, password: impl FnOnce() -> Result<String>) -> Result<Value> {
+        let mut keystore = match &self.keystore {
+            Some(keystore) => keystore.clone(),
+            None => encrypt_private_key(&self.private_key()?, &password()?)?,
+        };
+        // The address field is not required by the standard, but it is expected by geth
+        keystore["address"] = Value::String(
+            self.address
+                .strip_prefix("0x")
+                .unwrap_or(&self.address)
+                .to_string(),
+        );
+        Ok(keystore)
+    }
+    /// Returns the plain private key. For the encrypted accounts the password is requested.
+    pub fn private_key(&self) -> Result<String> {
+        Ok(hex::encode(self.local_signer()?.to_bytes()).to_ascii_lowercase())
+    }
+    /// Returns the signer, which holds the private key in memory.
+    /// For the encrypted accounts the password is requested.
+    fn local_signer(&self) -> Result<PrivateKeySigner> {
+        if self.is_watch_only() {
+            return Err(anyhow::anyhow!(
+                "The account {} is watch-only, it has no private key to sign with",
+                self.address
+            ));
+        }
+        if self.is_external() {
+            return Err(anyhow::anyhow!(
+                "The private key of '{}' is held by an external signer",
+                self.address
+            ));
+        }
+        let signer = match &self.keystore {
+            Some(keystore) => {
+                let password = read_password(&format!("Password for {}: ", self.address))?;
+                decrypt_keystore(keystore, &password)?
+            }
+            None => private_key_to_signer(&self.private_key)?,
+        };
+        if signer.address().to_string().to_ascii_lowercase() != self.address {
+            return Err(anyhow::anyhow!(
+                "The private key doesn't correspond to the address {}",
+                self.address
+            ));
+        }
+        Ok(signer)
+    }
+    /// Returns the signer of the account messages. For the encrypted accounts the password is requested.
+    pub fn signer(&self) -> Result<AccountSigner> {
+        match (&self.external, &self.remote) {
+            (Some(command), _) => Ok(AccountSigner::External(ExternalSigner {
+                command: command.clone(),
+                address: Address::from_str(&self.address)?,
+            })),
+            (None, Some(url)) => Ok(AccountSigner::Remote(RemoteSigner {
+                url: url.clone(),
+                address: Address::from_str(&self.address)?,
+            })),
+            (None, None) => Ok(AccountSigner::Local(self.local_signer()?)),
+        }
+    }
 }
 
+/// The BIP-32/44 derivation of an account private key from a BIP-39 mnemonic phrase.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Derivation {
+    /// The fingerprint of a mnemonic phrase: first 4 bytes of its keccak256 hash.
+    pub seed: String,
+    /// The BIP-32 derivation path, i.e. `m/44'/60'/0'/0/<index>`
+    pub path: String,
+    /// The index of the account in the derivation path
+    pub index: u32,
+}
+
+/// The BIP-44 derivation path of Ethereum accounts, without the trailing account index.
+pub const DERIVATION_PATH_PREFIX: &str = "m/44'/60'/0'/0/";
+
+/// The number of words in a generated mnemonic phrase.
+const MNEMONIC_WORD_COUNT: usize = 12;
+
 /// The simple representation of a VSL account, with private data
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Account {
@@ -31,6 +185,9 @@ pub struct Account {
     pub quorum: u16,
     /// The private key and address
     pub credentials: Credentials,
+    /// If the private key is derived from a mnemonic phrase, the seed and index it came from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub derivation: Option<Derivation>,
 }
 
 fn generate_private_key() -> String {
@@ -40,8 +197,39 @@ fn generate_private_key() -> String {
     hex::encode(secret_key.to_bytes()).to_ascii_lowercase()
 }
 
+/// Generates a new random BIP-39 mnemonic phrase.
+pub fn generate_mnemonic() -> Result<String> {
+    let mut rng = thread_rng();
+    Ok(Mnemonic::<English>::new_with_count(&mut rng, MNEMONIC_WORD_COUNT)?.to_phrase())
+}
+
+/// Derives the private key with the BIP-44 path `m/44'/60'/0'/0/<index>` from a mnemonic phrase.
+pub fn derive_private_key(phrase: &str, index: u32) -> Result<(String, Derivation)> {
+    // Normalize the whitespaces between the words
+    let phrase = phrase.split_whitespace().collect::<Vec<&str>>().join(" ");
+    let signer = MnemonicBuilder::<English>::default()
+        .phrase(phrase.clone())
+        .index(index)?
+        .build()
+        .map_err(|err| anyhow::anyhow!("Invalid mnemonic phrase: {}", err))?;
+    let derivation = Derivation {
+        seed: hex::encode(&keccak256(phrase.as_bytes())[..4]),
+        path: format!("{}{}", DERIVATION_PATH_PREFIX, index),
+        index: index,
+    };
+    Ok((
+        hex::encode(signer.to_bytes()).to_ascii_lowercase(),
+        derivation,
+    ))
+}
+
 impl Account {
-    fn new(name: String, credentials: Credentials, mut verifiers: Vec<String>) -> Self {
+    fn new(
+        name: String,
+        credentials: Credentials,
+        mut verifiers: Vec<String>,
+        derivation: Option<Derivation>,
+    ) -> Self {
         // Add the deafult verifier - the one with the address from the signer
         verifiers.push(credentials.address.clone());
         Account {
@@ -49,8 +237,60 @@ impl Account {
             signatures: verifiers,
             quorum: 1,
             credentials: credentials,
+            derivation: derivation,
         }
     }
+    /// Adds a verifier, to whom the claims of the account are addressed.
+    pub fn add_verifier(&mut self, address: String) -> Result<()> {
+        if self.signatures.contains(&address) {
+            return Err(anyhow::anyhow!(
+                "{} is already a verifier of '{}'",
+                address,
+                self.name
+            ));
+        }
+        self.signatures.push(address);
+        Ok(())
+    }
+    /// Removes a verifier. The quorum must not exceed the number of the remaining verifiers.
+    pub fn remove_verifier(&mut self, address: &str) -> Result<()> {
+        let position = self
+            .signatures
+            .iter()
+            .position(|verifier| verifier == address)
+            .ok_or(anyhow::anyhow!(
+                "{} is not a verifier of '{}'",
+                address,
+                self.name
+            ))?;
+        if self.signatures.len() - 1 < self.quorum as usize {
+            return Err(anyhow::anyhow!(
+                "Cannot remove {}: the quorum {} would exceed the number of verifiers {}. Please decrease the quorum first",
+                address,
+                self.quorum,
+                self.signatures.len() - 1
+            ));
+        }
+        self.signatures.remove(position);
+        Ok(())
+    }
+    /// Sets the minimum quorum of verifier signatures. It must be positive and must not
+    /// exceed the number of verifiers.
+    pub fn set_quorum(&mut self, quorum: u16) -> Result<()> {
+        if quorum == 0 {
+            return Err(anyhow::anyhow!("The quorum must be positive"));
+        }
+        if quorum as usize > self.signatures.len() {
+            return Err(anyhow::anyhow!(
+                "The quorum {} exceeds the number of verifiers {} of '{}'",
+                quorum,
+                self.signatures.len(),
+                self.name
+            ));
+        }
+        self.quorum = quorum;
+        Ok(())
+    }
 }
 
 /// The database of user accounts.
@@ -62,10 +302,15 @@ pub struct Accounts {
     using: String,
 }
 
-pub fn private_key_to_signer(private_key: &str) -> PrivateKeySigner {
-    let bytes = <[u8; 32]>::from_hex(private_key).expect("Could not extract private key");
-    let secret_key = SecretKey::from_bytes(&bytes.into()).expect("could not parse private key");
-    PrivateKeySigner::from(secret_key)
+pub fn private_key_to_signer(private_key: &str) -> Result<PrivateKeySigner> {
+    let private_key = private_key.trim();
+    let bytes =
+        <[u8; 32]>::from_hex(private_key.strip_prefix("0x").unwrap_or(private_key)).or(Err(
+            anyhow::anyhow!("Could not extract private key: must be a 32 bytes hex string"),
+        ))?;
+    let secret_key = SecretKey::from_bytes(&bytes.into())
+        .or(Err(anyhow::anyhow!("Could not parse private key")))?;
+    Ok(PrivateKeySigner::from(secret_key))
 }
 
 impl Accounts {
@@ -92,39 +337,102 @@ impl Accounts {
                             "Failed to read private key file: {}",
                             trimmed
                         )))?;
-                    key_str
+                    key_str.trim().to_string()
                 }
             }
             None => generate_private_key(),
         };
+        let address = private_key_to_signer(&private_key)?
+            .address()
+            .to_string()
+            .to_ascii_lowercase();
         if self
             .accounts
             .iter()
-            .find(|(_, account)| account.credentials.private_key == private_key)
+            .find(|(_, account)| account.credentials.address == address)
             .is_some()
         {
             return Err(anyhow::anyhow!("This private key is already registred"));
         }
         Ok(Credentials {
             private_key: private_key.clone(),
-            address: private_key_to_signer(&private_key)
-                .address()
-                .to_string()
-                .to_ascii_lowercase(),
+            address: address,
+            keystore: None,
+            external: None,
+            remote: None,
+        })
+    }
+    /// Generates the credentials from an encrypted V3 keystore and checks if the address is not used twice.
+    pub fn keystore_credentials(&self, keystore: Value, password: &str) -> Result<Credentials> {
+        let address = decrypt_keystore(&keystore, password)?
+            .address()
+            .to_string()
+            .to_ascii_lowercase();
+        if self
+            .accounts
+            .iter()
+            .find(|(_, account)| account.credentials.address == address)
+            .is_some()
+        {
+            return Err(anyhow::anyhow!("This private key is already registred"));
+        }
+        Ok(Credentials {
+            private_key: String::new(),
+            address: address,
+            keystore: Some(keystore),
+            external: None,
+            remote: None,
         })
     }
+    /// Generates the credentials, which delegate signing to an external signer process or
+    /// a remote signing service, and checks if the address is not used twice.
+    pub fn external_credentials(
+        &self,
+        address: &str,
+        command: Option<Vec<String>>,
+        url: Option<String>,
+    ) -> Result<Credentials> {
+        if command.as_ref().is_some_and(|command| command.is_empty()) {
+            return Err(anyhow::anyhow!("The external signer command is empty"));
+        }
+        let address = Address::from_str(address)
+            .or(Err(anyhow::anyhow!("Invalid address: {}", address)))?
+            .to_string()
+            .to_ascii_lowercase();
+        if self
+            .accounts
+            .iter()
+            .find(|(_, account)| account.credentials.address == address)
+            .is_some()
+        {
+            return Err(anyhow::anyhow!("This address is already registred"));
+        }
+        Ok(Credentials {
+            private_key: String::new(),
+            address: address,
+            keystore: None,
+            external: command,
+            remote: url,
+        })
+    }
+    /// Generates the credentials without a private key, i.e. for a watch-only account.
+    pub fn watch_credentials(&self, address: &str) -> Result<Credentials> {
+        // Neither an external signer, nor a remote one is set
+        self.external_credentials(address, None, None)
+    }
     /// Creates a new account with label `name` with a given private key.
     pub fn create(
         &mut self,
         name: String,
         credentials: Credentials,
+        derivation: Option<Derivation>,
         owerrwrite: bool,
     ) -> Result<Account> {
         if !owerrwrite && self.accounts.contains_key(&name) {
             return Err(anyhow::anyhow!("'{}' is already present", name));
         }
-        // TODO: pass the set verifiers as well??
-        let account = Account::new(name.clone(), credentials, Vec::new());
+        // The account itself is the only verifier initially, the others are added with `account:verifiers`
+        let account = Account::new(name.clone(), credentials, Vec::new(), derivation);
         self.accounts.insert(name.clone(), account);
         self.using = name.clone();
         self.get(Some(&name))
@@ -150,6 +458,14 @@ impl Accounts {
         self.using = name.to_string();
         Ok(())
     }
+    /// Encrypts the private key of the account with label `name`.
+    pub fn encrypt(&mut self, name: &str, password: &str) -> Result<()> {
+        self.accounts
+            .get_mut(name)
+            .ok_or(anyhow::anyhow!("account '{}' in not found", name))?
+            .credentials
+            .encrypt(password)
+    }
     /// Deletes the known account with label `name`.
     pub fn remove(&mut self, name: &str) -> Result<()> {
         self.accounts
@@ -157,8 +473,75 @@ impl Accounts {
             .ok_or(anyhow::anyhow!("failed to remove account '{}'", name))
             .map(|_| {})
     }
+    /// Applies the `update` to the known account with label `name`. If `name` is omitted, the current is used.
+    pub fn update(
+        &mut self,
+        nm: Option<&str>,
+        update: impl FnOnce(&mut Account) -> Result<()>,
+    ) -> Result<Account> {
+        let name = nm.unwrap_or(&self.using).to_string();
+        if name == "" {
+            return Err(anyhow::anyhow!(
+                "Currently not using any account. Please create an account first."
+            ));
+        }
+        let account = self
+            .accounts
+            .get_mut(&name)
+            .ok_or(anyhow::anyhow!("account '{}' in not found", name))?;
+        update(account)?;
+        Ok(account.clone())
+    }
     /// Lists all accounts.
     pub fn list(&self) -> Vec<(&String, &Account)> {
         self.accounts.iter().collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHRASE: &str = "test test test test test test test test test test test junk";
+
+    #[test]
+    fn test_derive_private_key() {
+        let (private_key, derivation) = derive_private_key(PHRASE, 0).expect("must derive");
+        assert_eq!(
+            private_key,
+            "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+        );
+        assert_eq!(derivation.path, "m/44'/60'/0'/0/0");
+        let (other_key, other_derivation) = derive_private_key(PHRASE, 1).expect("must derive");
+        assert_ne!(private_key, other_key);
+        assert_eq!(derivation.seed, other_derivation.seed);
+    }
+
+    #[test]
+    fn test_verifiers_and_quorum() {
+        let mut accounts = Accounts::default();
+        let credentials = accounts.generate_credentials(None).expect("must generate");
+        let address = credentials.address.clone();
+        let mut account = accounts
+            .create("acc".to_string(), credentials, None, false)
+            .expect("must create");
+        assert_eq!(account.signatures, vec![address.clone()]);
+        let verifier = "0x0000000000000000000000000000000000000001".to_string();
+        account.add_verifier(verifier.clone()).expect("must add");
+        assert!(account.add_verifier(verifier.clone()).is_err());
+        assert!(account.set_quorum(3).is_err());
+        assert!(account.set_quorum(0).is_err());
+        account.set_quorum(2).expect("must set quorum");
+        assert!(account.remove_verifier(&verifier).is_err());
+        account.set_quorum(1).expect("must set quorum");
+        account.remove_verifier(&verifier).expect("must remove");
+        assert_eq!(account.signatures, vec![address]);
+    }
+
+    #[test]
+    fn test_generate_mnemonic() {
+        let phrase = generate_mnemonic().expect("must generate");
+        assert_eq!(phrase.split_whitespace().count(), MNEMONIC_WORD_COUNT);
+        assert!(derive_private_key(&phrase, 7).is_ok());
+    }
+}
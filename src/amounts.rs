@@ -0,0 +1,153 @@
+#![allow(unused)]
+
+use alloy::primitives::U256;
+use anyhow::Result;
+use anyhow::anyhow;
+use serde_json::Value;
+
+/// The number of decimals of the native VSL token.
+pub const VSL_DECIMALS: u8 = 18;
+
+/// Parses an integer amount of base units: a hexadecimal (`0x`-prefixed) or a decimal integer.
+pub fn parse_units(s: &str) -> Result<U256> {
+    let s = s.trim();
+    let parsed = match s.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16),
+        None => U256::from_str_radix(s, 10),
+    };
+    parsed.map_err(|_| {
+        anyhow!(
+            "Invalid number format: {}, must be a hexadecimal or decimal integer",
+            s
+        )
+    })
+}
+
+/// Parses an amount. An integer is the amount of base units, as is. A decimal number with
+/// a point, like `1.5`, is the amount of whole tokens: it is scaled by `10^decimals`.
+pub fn parse_amount(s: &str, decimals: u8) -> Result<U256> {
+    let s = s.trim();
+    let Some((whole, fraction)) = s.split_once('.') else {
+        return parse_units(s);
+    };
+    let invalid = || {
+        anyhow!(
+            "Invalid amount: {}, must be an integer or a decimal number like 1.5",
+            s
+        )
+    };
+    let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+        return Err(invalid());
+    }
+    if fraction.len() > decimals as usize {
+        return Err(anyhow!(
+            "Invalid amount: {}, at most {} decimal places are allowed",
+            s,
+            decimals
+        ));
+    }
+    let overflow = || anyhow!("Invalid amount: {}, it is too large", s);
+    let whole = if whole.is_empty() {
+        U256::ZERO
+    } else {
+        U256::from_str_radix(whole, 10).map_err(|_| overflow())?
+    };
+    // Pad the fraction with zeros up to `decimals` digits: `1.5` with 3 decimals is `1500`
+    let fraction = format!("{:0<width$}", fraction, width = decimals as usize);
+    let fraction = if fraction.is_empty() {
+        U256::ZERO
+    } else {
+        U256::from_str_radix(&fraction, 10).map_err(|_| overflow())?
+    };
+    whole
+        .checked_mul(scale(decimals).ok_or_else(overflow)?)
+        .and_then(|whole| whole.checked_add(fraction))
+        .ok_or_else(overflow)
+}
+
+/// Formats an amount of base units as a decimal number of whole tokens, i.e. `1.5`.
+/// The point is always present, so that the result is parsed back by `parse_amount`
+/// as the same amount.
+pub fn format_amount(amount: U256, decimals: u8) -> String {
+    let Some(scale) = scale(decimals) else {
+        return amount.to_string();
+    };
+    if decimals == 0 {
+        return amount.to_string();
+    }
+    let whole = amount / scale;
+    let fraction = amount % scale;
+    if fraction.is_zero() {
+        format!("{}.0", whole)
+    } else {
+        let fraction = format!(
+            "{:0>width$}",
+            fraction.to_string(),
+            width = decimals as usize
+        );
+        format!("{}.{}", whole, fraction.trim_end_matches('0'))
+    }
+}
+
+/// Reads an amount of base units from a JSON value: a hex or decimal string, or a number.
+pub fn amount_from_value(value: &Value) -> Option<U256> {
+    match value {
+        Value::String(s) => parse_units(s).ok(),
+        Value::Number(n) => n.as_u64().map(U256::from),
+        _ => None,
+    }
+}
+
+/// The hexadecimal representation of an amount, which is used in RPC messages
+pub fn to_hex(amount: U256) -> String {
+    format!("0x{:x}", amount)
+}
+
+fn scale(decimals: u8) -> Option<U256> {
+    U256::from(10u64).checked_pow(U256::from(decimals))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("100", 18).unwrap(), U256::from(100u64));
+        assert_eq!(parse_amount("0x100", 18).unwrap(), U256::from(256u64));
+        assert_eq!(parse_amount("1.5", 3).unwrap(), U256::from(1500u64));
+        assert_eq!(parse_amount(".25", 2).unwrap(), U256::from(25u64));
+        assert_eq!(parse_amount("2.", 2).unwrap(), U256::from(200u64));
+        assert_eq!(
+            parse_amount("1000000", 18).unwrap(),
+            U256::from(1_000_000u64)
+        );
+        assert_eq!(
+            parse_amount("1000000.0", 18).unwrap(),
+            parse_units("1000000000000000000000000").unwrap()
+        );
+        assert!(parse_amount("1.234", 2).is_err());
+        assert!(parse_amount("1.2.3", 18).is_err());
+        assert!(parse_amount("-1.5", 18).is_err());
+        assert!(parse_amount(".", 18).is_err());
+        assert!(parse_amount("1e18", 18).is_err());
+    }
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(format_amount(U256::from(1500u64), 3), "1.5");
+        assert_eq!(format_amount(U256::from(1000u64), 3), "1.0");
+        assert_eq!(format_amount(U256::from(5u64), 3), "0.005");
+        assert_eq!(format_amount(U256::from(5u64), 0), "5");
+        assert_eq!(
+            format_amount(parse_units("0xd3c21bcecceda1000000").unwrap(), 18),
+            "1000000.0"
+        );
+        let amount = parse_units("123456789012345678901").unwrap();
+        assert_eq!(
+            parse_amount(&format_amount(amount, 18), 18).unwrap(),
+            amount
+        );
+    }
+}
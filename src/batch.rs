@@ -0,0 +1,286 @@
+#![allow(unused)]
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// A row of a batch of transfers: the recipient, a name or an address, and the amount
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchRow {
+    pub recipient: String,
+    pub amount: String,
+}
+
+/// The status of a row in a batch report
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RowStatus {
+    /// The transfer is accepted by the network
+    Ok,
+    /// The transfer is failed
+    Failed,
+    /// The transfer was accepted in a previous run, it is not repeated
+    Skipped,
+}
+
+/// The result of a row of a batch
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct RowResult {
+    /// The number of the row in the batch, starting from 1
+    pub row: usize,
+    pub recipient: String,
+    pub amount: String,
+    pub status: RowStatus,
+    /// The response of the network: the claim id of the transfer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RowResult {
+    /// Is the row of the batch already transferred
+    pub fn is_done(&self) -> bool {
+        self.status != RowStatus::Failed
+    }
+}
+
+/// Reads the rows of a batch from a file. A JSON file is an array of objects with the `to`
+/// (or `recipient`) and `amount` fields. Any other file is a CSV with the `recipient,amount`
+/// lines, the header line, empty lines and the lines, starting with `#`, are skipped.
+pub fn read_rows(file: &str) -> Result<Vec<BatchRow>> {
+    let content = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read the batch from '{}'", file))?;
+    let rows = if content.trim_start().starts_with('[') {
+        parse_json_rows(&content)
+    } else {
+        parse_csv_rows(&content)
+    }
+    .with_context(|| format!("Invalid batch in '{}'", file))?;
+    if rows.is_empty() {
+        return Err(anyhow!("The batch in '{}' is empty", file));
+    }
+    Ok(rows)
+}
+
+fn parse_json_rows(content: &str) -> Result<Vec<BatchRow>> {
+    let values: Vec<Value> = serde_json::from_str(content)?;
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let field = |names: &[&str]| {
+                names
+                    .iter()
+                    .find_map(|name| match value.get(name) {
+                        Some(Value::String(str)) => Some(str.clone()),
+                        Some(Value::Number(number)) => Some(number.to_string()),
+                        _ => None,
+                    })
+                    .ok_or(anyhow!(
+                        "row {}: `{}` is absent, got: {}",
+                        i + 1,
+                        names.join("` or `"),
+                        value
+                    ))
+            };
+            Ok(BatchRow {
+                recipient: field(&["to", "recipient"])?,
+                amount: field(&["amount"])?,
+            })
+        })
+        .collect()
+}
+
+fn parse_csv_rows(content: &str) -> Result<Vec<BatchRow>> {
+    let mut rows = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line
+            .split(',')
+            .map(|field| field.trim().trim_matches('"').trim())
+            .collect();
+        let [recipient, amount] = fields.as_slice() else {
+            return Err(anyhow!(
+                "line {}: must be `recipient,amount`, got: '{}'",
+                i + 1,
+                line
+            ));
+        };
+        let is_header = rows.is_empty()
+            && ["to", "recipient"].contains(&recipient.to_lowercase().as_str())
+            && amount.eq_ignore_ascii_case("amount");
+        if !is_header {
+            rows.push(BatchRow {
+                recipient: recipient.to_string(),
+                amount: amount.to_string(),
+            });
+        }
+    }
+    Ok(rows)
+}
+
+/// The default report file of a batch: `payouts.csv` is reported to `payouts.csv.report.json`
+pub fn report_file(file: &str) -> String {
+    format!("{}.report.json", file)
+}
+
+/// Reads the report of a previous run of a batch, if it exists.
+pub fn read_report(file: &str) -> Result<Vec<RowResult>> {
+    if !Path::new(file).exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read the batch report from '{}'", file))?;
+    serde_json::from_str(&content).with_context(|| format!("Invalid batch report in '{}'", file))
+}
+
+/// Writes the report of a batch.
+pub fn write_report(file: &str, results: &Vec<RowResult>) -> Result<()> {
+    fs::write(file, serde_json::to_string_pretty(results)?)
+        .with_context(|| format!("Failed to write the batch report to '{}'", file))
+}
+
+/// Matches the rows of a batch with the results of a previous run, and returns the results
+/// of the rows, which are already transferred. The rows are matched by the recipient and the amount,
+/// not by their numbers, so that a row, inserted or removed before the retry, does not shift
+/// the matches: the n-th row of a recipient and an amount matches the n-th result of them.
+pub fn find_done<'a>(previous: &'a [RowResult], rows: &[BatchRow]) -> Vec<Option<&'a RowResult>> {
+    let mut previous: Vec<&RowResult> = previous.iter().collect();
+    previous.sort_by_key(|result| result.row);
+    let mut occurrences: HashMap<(&str, &str), usize> = HashMap::new();
+    rows.iter()
+        .map(|row| {
+            let key = (row.recipient.as_str(), row.amount.as_str());
+            let occurrence = occurrences.entry(key).or_default();
+            let result = previous
+                .iter()
+                .filter(|result| result.recipient == row.recipient && result.amount == row.amount)
+                .nth(*occurrence)
+                .copied();
+            *occurrence += 1;
+            result.filter(|result| result.is_done())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(recipient: &str, amount: &str) -> BatchRow {
+        BatchRow {
+            recipient: recipient.to_string(),
+            amount: amount.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_rows() {
+        let csv = "recipient,amount\n# payouts\nalice, 1.5\n\n\"0x01\",100\n";
+        assert_eq!(
+            parse_csv_rows(csv).unwrap(),
+            vec![row("alice", "1.5"), row("0x01", "100")]
+        );
+        assert!(parse_csv_rows("alice,1,2").is_err());
+        let json = r#"[{"to": "alice", "amount": "1.5"}, {"recipient": "bob", "amount": 100}]"#;
+        assert_eq!(
+            parse_json_rows(json).unwrap(),
+            vec![row("alice", "1.5"), row("bob", "100")]
+        );
+        assert!(parse_json_rows(r#"[{"to": "alice"}]"#).is_err());
+    }
+
+    #[test]
+    fn test_find_done() {
+        let previous = vec![
+            RowResult {
+                row: 1,
+                recipient: "alice".to_string(),
+                amount: "1.5".to_string(),
+                status: RowStatus::Ok,
+                result: Some("0x01".to_string()),
+                error: None,
+            },
+            RowResult {
+                row: 2,
+                recipient: "bob".to_string(),
+                amount: "100".to_string(),
+                status: RowStatus::Failed,
+                result: None,
+                error: Some("insufficient balance".to_string()),
+            },
+        ];
+        let done = |rows: &[BatchRow]| -> Vec<Option<usize>> {
+            find_done(&previous, rows)
+                .into_iter()
+                .map(|result| result.map(|result| result.row))
+                .collect()
+        };
+        assert_eq!(
+            done(&[row("alice", "1.5"), row("bob", "100")]),
+            vec![Some(1), None]
+        );
+        assert_eq!(done(&[row("alice", "2")]), vec![None]);
+        // A row, inserted before the transferred one, does not make it transferred again
+        assert_eq!(
+            done(&[row("carol", "5"), row("alice", "1.5"), row("bob", "100")]),
+            vec![None, Some(1), None]
+        );
+        // The second payment of the same amount to the same recipient is not transferred yet
+        assert_eq!(
+            done(&[row("alice", "1.5"), row("alice", "1.5")]),
+            vec![Some(1), None]
+        );
+    }
+
+    #[test]
+    fn test_resume() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let batch = dir.path().join("payouts.csv").to_string_lossy().to_string();
+        fs::write(&batch, "alice,1\nbob,2\n").unwrap();
+        let rows = read_rows(&batch).unwrap();
+        let report = report_file(&batch);
+        assert!(read_report(&report).unwrap().is_empty());
+        // The first run has transferred to bob, and failed to transfer to alice
+        let results = vec![
+            RowResult {
+                row: 1,
+                recipient: "alice".to_string(),
+                amount: "1".to_string(),
+                status: RowStatus::Failed,
+                result: None,
+                error: Some("insufficient balance".to_string()),
+            },
+            RowResult {
+                row: 2,
+                recipient: "bob".to_string(),
+                amount: "2".to_string(),
+                status: RowStatus::Ok,
+                result: Some("0x02".to_string()),
+                error: None,
+            },
+        ];
+        write_report(&report, &results).unwrap();
+        // The failed row is fixed by replacing it with two rows before the retry
+        fs::write(&batch, "alice,0.5\nalice,0.5\nbob,2\n").unwrap();
+        let rows = read_rows(&batch).unwrap();
+        let previous = read_report(&report).unwrap();
+        let done = find_done(&previous, &rows);
+        assert!(done[0].is_none());
+        assert!(done[1].is_none());
+        assert_eq!(
+            done[2].map(|result| result.result.clone()),
+            Some(Some("0x02".to_string()))
+        );
+    }
+}
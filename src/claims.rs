@@ -0,0 +1,228 @@
+#![allow(unused)]
+
+use crate::schema::validate;
+use anyhow::Result;
+use anyhow::anyhow;
+use schemars::JsonSchema;
+use schemars::schema_for;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+/// The state of a claim in its lifecycle
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ClaimState {
+    /// The claim is submitted, but is not settled yet
+    Submitted,
+    /// The claim is verified and settled
+    Settled,
+    /// The claim was not settled before its expiration time
+    Expired,
+    /// The claim is neither known locally, nor settled
+    Unknown,
+}
+
+impl fmt::Display for ClaimState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let state = match self {
+            ClaimState::Submitted => "submitted",
+            ClaimState::Settled => "settled",
+            ClaimState::Expired => "expired",
+            ClaimState::Unknown => "unknown",
+        };
+        write!(f, "{}", state)
+    }
+}
+
+impl ClaimState {
+    /// The state of a claim: `expires` is the expiration time of the submitted claim, if it is
+    /// known locally, and `settled` tells if the network has the settled claim.
+    pub fn of(expires: Option<u64>, settled: bool, now: u64) -> ClaimState {
+        match (settled, expires) {
+            (true, _) => ClaimState::Settled,
+            (false, Some(expires)) if expires <= now => ClaimState::Expired,
+            (false, Some(_)) => ClaimState::Submitted,
+            (false, None) => ClaimState::Unknown,
+        }
+    }
+
+    /// The claim will not change its state anymore
+    pub fn is_final(&self) -> bool {
+        matches!(self, ClaimState::Settled | ClaimState::Expired)
+    }
+}
+
+/// A registered claim type with the JSON Schemas of its claims and proofs
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ClaimType {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The schema of the claim body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Value>,
+    /// The schema of the proof body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof_schema: Option<Value>,
+}
+
+impl ClaimType {
+    /// Validates the claim and the proof against the schemas of the type. A body, which
+    /// is a valid JSON, is validated as JSON, any other body is validated as a JSON string.
+    pub fn check(&self, claim: &str, proof: &str) -> Result<()> {
+        let mut errors = Vec::new();
+        for (what, schema, body) in [
+            ("claim", &self.schema, claim),
+            ("proof", &self.proof_schema, proof),
+        ] {
+            if let Some(schema) = schema {
+                let value = serde_json::from_str(body).unwrap_or(Value::String(body.to_string()));
+                for error in validate(schema, &value) {
+                    errors.push(format!("{} {}", what, error));
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "The claim does not match its type:\n  {}",
+                errors.join("\n  ")
+            ))
+        }
+    }
+}
+
+/// The claim body of the built-in `vsl:attestation` type
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AttestationClaim {
+    /// Whom the statement is about, i.e. an address or a name
+    pub subject: String,
+    /// What is attested about the subject
+    pub statement: String,
+}
+
+/// The claim body of the built-in `vsl:transfer` type
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TransferClaim {
+    /// The chain, where the transfer is made
+    pub chain: String,
+    /// The sender on the chain
+    pub from: String,
+    /// The receiver on the chain
+    pub to: String,
+    /// The transferred amount in the smallest units of the asset
+    pub amount: String,
+    /// The transferred asset, the native one if it is not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset: Option<String>,
+}
+
+/// The built-in claim types, which are known in every config, by their names. Their schemas
+/// are generated from the types of their bodies.
+pub fn builtin_claim_types() -> Vec<(&'static str, ClaimType)> {
+    let claim_type = |description: &str, schema: schemars::schema::RootSchema| ClaimType {
+        description: Some(description.to_string()),
+        schema: serde_json::to_value(schema).ok(),
+        proof_schema: None,
+    };
+    vec![
+        (
+            "vsl:attestation",
+            claim_type("A statement about a subject", schema_for!(AttestationClaim)),
+        ),
+        (
+            "vsl:transfer",
+            claim_type(
+                "A transfer of an asset on another chain",
+                schema_for!(TransferClaim),
+            ),
+        ),
+    ]
+}
+
+/// The built-in claim type of the `name`
+pub fn builtin_claim_type(name: &str) -> Option<ClaimType> {
+    builtin_claim_types()
+        .into_iter()
+        .find(|(builtin, _)| *builtin == name)
+        .map(|(_, claim_type)| claim_type)
+}
+
+/// Checks if a string in the `value`, at any depth, is equal to the `needle`, i.e.
+/// a settled claim in a list of settled claims has the claim id.
+pub fn contains_string(value: &Value, needle: &str) -> bool {
+    match value {
+        Value::String(str) => str.eq_ignore_ascii_case(needle),
+        Value::Array(values) => values.iter().any(|value| contains_string(value, needle)),
+        Value::Object(map) => map.values().any(|value| contains_string(value, needle)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_claim_state() {
+        assert_eq!(ClaimState::of(Some(10), true, 20), ClaimState::Settled);
+        assert_eq!(ClaimState::of(Some(10), false, 20), ClaimState::Expired);
+        assert_eq!(ClaimState::of(Some(30), false, 20), ClaimState::Submitted);
+        assert_eq!(ClaimState::of(None, false, 20), ClaimState::Unknown);
+        assert!(ClaimState::Expired.is_final());
+        assert!(!ClaimState::Unknown.is_final());
+        assert_eq!(ClaimState::Settled.to_string(), "settled");
+    }
+
+    #[test]
+    fn test_claim_type_check() {
+        let claim_type = ClaimType {
+            description: None,
+            schema: Some(json!({ "type": "object", "required": ["age"] })),
+            proof_schema: Some(json!({ "type": "string", "minLength": 1 })),
+        };
+        assert!(claim_type.check(r#"{"age": 21}"#, "signature").is_ok());
+        let err = claim_type.check("age: 21", "").unwrap_err().to_string();
+        assert!(err.contains("claim $: "));
+        assert!(err.contains("proof $: "));
+        assert!(ClaimType::default().check("anything", "").is_ok());
+    }
+
+    #[test]
+    fn test_builtin_claim_types() {
+        for (name, claim_type) in builtin_claim_types() {
+            let schema = claim_type.schema.unwrap();
+            assert!(crate::schema::check_schema(&schema).is_ok(), "{}", name);
+        }
+        let transfer = builtin_claim_type("vsl:transfer").unwrap();
+        let claim = serde_json::to_string(&TransferClaim {
+            chain: "sepolia".to_string(),
+            from: "0xab".to_string(),
+            to: "0xcd".to_string(),
+            amount: "100".to_string(),
+            asset: None,
+        })
+        .unwrap();
+        assert!(transfer.check(&claim, "").is_ok());
+        assert!(
+            transfer
+                .check(r#"{"chain": "sepolia", "amount": 1}"#, "")
+                .is_err()
+        );
+        assert!(transfer.check("Cross-chain token transfer", "").is_err());
+        assert!(builtin_claim_type("vsl:unknown").is_none());
+    }
+
+    #[test]
+    fn test_contains_string() {
+        let claims = json!([{ "claim": { "id": "0xAB", "claim": "x" } }, { "claim": "y" }]);
+        assert!(contains_string(&claims, "0xab"));
+        assert!(contains_string(&claims, "y"));
+        assert!(!contains_string(&claims, "z"));
+    }
+}
@@ -1,9 +1,14 @@
+use crate::inputs::DEFAULT_MAX_INPUT_SIZE;
+use crate::inputs::Encoding;
 use crate::networks::VSL_CLI_DEFAULT_NETWORK_PORT;
 use crate::networks::VSL_CLI_DEFAULT_NETWORK_URL;
+use crate::output::OutputFormat;
 
 use clap::ArgAction;
+use clap::Args;
 use clap::Parser;
 use clap::Subcommand;
+use clap::ValueEnum;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -11,6 +16,43 @@ use clap::Subcommand;
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+    /// The format of the output. In REPL it may be given for a single command.
+    #[arg(long, global = true, value_enum)]
+    pub output: Option<OutputFormat>,
+    /// The jq-like query, which filters the command result, i.e. `.[0].id`.
+    /// In REPL it may be also given after a command as `| <query>`.
+    #[arg(long, global = true)]
+    pub query: Option<String>,
+    /// Print the requests, which change the state, instead of sending them: the method
+    /// and the parameters with the signed message. In REPL it may be given for a single command.
+    #[arg(long, global = true, default_value_t = false)]
+    pub dry_run: bool,
+}
+
+/// The options of signing a message offline, shared by the commands, which send signed messages
+#[derive(Args, Clone, Debug, Default)]
+pub struct SignOnlyArgs {
+    /// Sign the message with the `--nonce` and print it, or write it to the `--out` file,
+    /// without sending it to the network. The message is sent later by `tx:send`.
+    #[arg(long, default_value_t = false, requires = "nonce")]
+    pub sign_only: bool,
+    /// The nonce of the message. By default it is requested from the network.
+    #[arg(long, default_value = None)]
+    pub nonce: Option<u64>,
+    /// The file to write the signed message to, with `--sign-only`. By default it is printed.
+    #[arg(long, default_value = None, requires = "sign_only")]
+    pub out: Option<String>,
+}
+
+/// The action over the verifiers of an account
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum VerifiersAction {
+    /// Add a verifier
+    Add,
+    /// Remove a verifier
+    Remove,
+    /// List the verifiers and the quorum
+    List,
 }
 
 #[derive(Subcommand)]
@@ -20,26 +62,42 @@ pub enum Commands {
     #[command(name = "claim:submit")]
     ClaimSubmit {
         /// the claim to be submitted
-        #[arg(help = "Request verification of a claim")]
+        #[arg(
+            help = "Request verification of a claim. `@<path>` reads the claim from a file, `-` from stdin"
+        )]
         claim: String,
         /// the claim type
         #[arg(short = 't', long = "type", default_value = "")]
         claim_type: String,
-        /// the proof of the claim
+        /// the proof of the claim. `@<path>` reads the proof from a file, `-` from stdin
         #[arg(short, long, default_value = "")]
         proof: String,
-        /// The expiration timestamp, when the submitted claim will be erased.
-        #[arg(short, long, default_value = None)]
-        expires: Option<u64>,
-        /// how much the claim is considered alive after creation, seconds. Default is 1 hour.
-        #[arg(short, long, default_value = "3600")]
-        lifetime: Option<u64>,
-        /// the total fee for verification and claim validation. Must be non-negative integer.
+        /// The encoding of the claim: `raw` (UTF-8 text as is), `base64` or `hex`
+        #[arg(long, value_enum, default_value_t = Encoding::Raw)]
+        claim_encoding: Encoding,
+        /// The encoding of the proof: `raw` (UTF-8 text as is), `base64` or `hex`
+        #[arg(long, value_enum, default_value_t = Encoding::Raw)]
+        proof_encoding: Encoding,
+        /// The maximal size of the claim and of the proof in bytes, after encoding
+        #[arg(long, default_value_t = DEFAULT_MAX_INPUT_SIZE)]
+        max_size: usize,
+        /// The expiration timestamp, when the submitted claim will be erased: Unix seconds,
+        /// RFC3339 date like `2026-10-20T12:00:00Z`, or relative like `now+2h`.
+        #[arg(short, long, default_value = None, conflicts_with = "lifetime", allow_hyphen_values = true)]
+        expires: Option<String>,
+        /// how much the claim is considered alive after creation: seconds, or a duration
+        /// like `90m` or `3d`. Default is 1 hour.
+        #[arg(short, long, default_value = "1h")]
+        lifetime: Option<String>,
+        /// the total fee for verification and claim validation: an integer of base units,
+        /// or a decimal number of VSL tokens, like `0.5`.
         #[arg(short, long, default_value = "0x1")]
         fee: String,
         /// URL to connect to, or name of a known network
         #[arg(short, long, default_value = None)]
         network: Option<String>,
+        #[command(flatten)]
+        signing: SignOnlyArgs,
     },
     /// Submit a verified claim for validation only
     #[command(name = "claim:settle")]
@@ -53,6 +111,8 @@ pub enum Commands {
         /// URL to connect to, or name of a known network
         #[arg(short, long, default_value = None)]
         network: Option<String>,
+        #[command(flatten)]
+        signing: SignOnlyArgs,
     },
     /// Fetch verification request claims targeted for a verifier address since a timestamp
     #[command(name = "claim:submitted")]
@@ -60,15 +120,20 @@ pub enum Commands {
         /// Client address. By default the current account address is used.
         #[arg(short, long, default_value = None)]
         address: Option<String>,
-        /// Since the timestamp
-        #[arg(short, long, default_value = None)]
-        since: Option<u64>,
-        /// Within a certain number seconds before now. Default value is 1 hour.
-        #[arg(short, long, default_value = "3600")]
-        within: Option<u64>,
+        /// Since the timestamp: Unix seconds, RFC3339 date like `2026-10-20T12:00:00Z`,
+        /// or relative like `now-2h` or `-30m`.
+        #[arg(short, long, default_value = None, conflicts_with = "within", allow_hyphen_values = true)]
+        since: Option<String>,
+        /// Within a certain time before now: seconds, or a duration like `90m` or `3d`.
+        /// Default value is 1 hour.
+        #[arg(short, long, default_value = "1h")]
+        within: Option<String>,
         /// URL to connect to, or name of a known network
         #[arg(short, long, default_value = None)]
         network: Option<String>,
+        /// Print the timestamps as Unix seconds, as they are returned by the node
+        #[arg(long, default_value_t = false)]
+        raw: bool,
     },
     /// Fetch verified claims targeted for a client address since a timestamp
     #[command(name = "claim:settled")]
@@ -76,15 +141,20 @@ pub enum Commands {
         /// Client address. By default the current account addess is used.
         #[arg(short, long, default_value = None)]
         address: Option<String>,
-        /// Since the timestamp
-        #[arg(short, long, default_value = None)]
-        since: Option<u64>,
-        /// Within a certain number seconds before now. Default value is 1 hour.
-        #[arg(short, long, default_value = "3600")]
-        within: Option<u64>,
+        /// Since the timestamp: Unix seconds, RFC3339 date like `2026-10-20T12:00:00Z`,
+        /// or relative like `now-2h` or `-30m`.
+        #[arg(short, long, default_value = None, conflicts_with = "within", allow_hyphen_values = true)]
+        since: Option<String>,
+        /// Within a certain time before now: seconds, or a duration like `90m` or `3d`.
+        /// Default value is 1 hour.
+        #[arg(short, long, default_value = "1h")]
+        within: Option<String>,
         /// URL to connect to, or name of a known network
         #[arg(short, long, default_value = None)]
         network: Option<String>,
+        /// Print the timestamps as Unix seconds, as they are returned by the node
+        #[arg(long, default_value_t = false)]
+        raw: bool,
     },
     /// Fetch a claim and its metadata by its ID
     #[command(name = "claim:get")]
@@ -95,6 +165,130 @@ pub enum Commands {
         /// URL to connect to, or name of a known network
         #[arg(short, long, default_value = None)]
         network: Option<String>,
+        /// Print the timestamps as Unix seconds, as they are returned by the node
+        #[arg(long, default_value_t = false)]
+        raw: bool,
+    },
+    /// Show the state of a claim: submitted, settled, expired or unknown
+    #[command(name = "claim:status")]
+    ClaimStatus {
+        /// The claim: its text, its id, or a name of the id
+        claim: String,
+        /// URL to connect to, or name of a known network
+        #[arg(short, long, default_value = None)]
+        network: Option<String>,
+        /// Print the timestamps as Unix seconds, as they are returned by the node
+        #[arg(long, default_value_t = false)]
+        raw: bool,
+    },
+    /// Wait until a claim is settled. Fails, if the claim expires, or the timeout passes.
+    #[command(name = "claim:wait")]
+    ClaimWait {
+        /// The claim: its text, its id, or a name of the id
+        claim: String,
+        /// How long to wait: seconds, or a duration like `90s` or `5m`
+        #[arg(short, long, default_value = "5m")]
+        timeout: String,
+        /// How often to check the claim state: seconds, or a duration like `10s`
+        #[arg(short, long, default_value = "5s")]
+        interval: String,
+        /// URL to connect to, or name of a known network
+        #[arg(short, long, default_value = None)]
+        network: Option<String>,
+        /// Print the timestamps as Unix seconds, as they are returned by the node
+        #[arg(long, default_value_t = false)]
+        raw: bool,
+    },
+    /// List the submitted claims, which are not settled yet
+    #[command(name = "claim:list")]
+    ClaimList {
+        /// Only the claims, which have expired before they were settled
+        #[arg(long, default_value_t = false, conflicts_with = "pending")]
+        expired: bool,
+        /// Only the claims, which have not expired yet
+        #[arg(long, default_value_t = false)]
+        pending: bool,
+        /// Print the timestamps as Unix seconds
+        #[arg(long, default_value_t = false)]
+        raw: bool,
+    },
+    /// Show a submitted claim, which is not settled yet
+    #[command(name = "claim:show")]
+    ClaimShow {
+        /// The claim: its text, its id, or a name of the id
+        claim: String,
+        /// Print the timestamps as Unix seconds
+        #[arg(long, default_value_t = false)]
+        raw: bool,
+    },
+    /// Forget a submitted claim, or all expired claims. The claims on the network are not affected.
+    #[command(name = "claim:forget")]
+    ClaimForget {
+        /// The claim: its text, its id, or a name of the id
+        #[arg(required_unless_present = "expired", conflicts_with = "expired")]
+        claim: Option<String>,
+        /// Forget all claims, which have expired before they were settled
+        #[arg(long, default_value_t = false)]
+        expired: bool,
+    },
+    /// Register a claim type. The claims of the type are validated by `claim:submit` before signing.
+    #[command(name = "claim-type:add")]
+    ClaimTypeAdd {
+        /// The name of the type, as it is passed to `claim:submit --type`
+        name: String,
+        /// The JSON Schema file of the claim body
+        #[arg(short, long, default_value = None)]
+        schema: Option<String>,
+        /// The JSON Schema file of the proof body
+        #[arg(short, long, default_value = None)]
+        proof_schema: Option<String>,
+        /// The description of the type
+        #[arg(short, long, default_value = None)]
+        description: Option<String>,
+        /// Replace the type, if it is already registered
+        #[arg(long, default_value_t = false)]
+        overwrite: bool,
+    },
+    /// List the registered claim types
+    #[command(name = "claim-type:list")]
+    ClaimTypeList {},
+    /// Remove a registered claim type
+    #[command(name = "claim-type:remove")]
+    ClaimTypeRemove {
+        /// The name of the type
+        name: String,
+    },
+    /// Act as a verifier: check the claims, submitted to the account, and settle the accepted ones
+    #[command(name = "verifier:run")]
+    VerifierRun {
+        /// The verifier account
+        #[arg(short, long)]
+        account: String,
+        /// The checker command, run with a shell for every claim. The claim with its proof is
+        /// written to its stdin as JSON. Exit code 0 accepts the claim, 1 rejects it.
+        #[arg(short, long)]
+        program: String,
+        /// How often to poll the submitted claims: seconds, or a duration like `30s` or `5m`
+        #[arg(short, long, default_value = "10s")]
+        interval: String,
+        /// How long a checker may run for a claim: seconds, or a duration like `2m`
+        #[arg(long, default_value = "60s")]
+        program_timeout: String,
+        /// Poll the claims, submitted since the timestamp: Unix seconds, RFC3339 date like
+        /// `2026-10-20T12:00:00Z`, or relative like `now-2h`. By default the polling continues
+        /// from the previous run, or starts 1 hour ago.
+        #[arg(short, long, default_value = None, allow_hyphen_values = true)]
+        since: Option<String>,
+        /// The file of the verifier progress. By default it is `<config>.verifier.<account>.json`
+        /// next to the config file.
+        #[arg(long, default_value = None)]
+        state: Option<String>,
+        /// Poll the claims once and exit, instead of running until interrupted
+        #[arg(long, default_value_t = false)]
+        once: bool,
+        /// URL to connect to, or name of a known network
+        #[arg(short, long, default_value = None)]
+        network: Option<String>,
     },
 
     #[command(subcommand_help_heading = "Payment commands")]
@@ -103,12 +297,31 @@ pub enum Commands {
         /// Recipient of the transfer
         #[arg(short, long)]
         to: String,
-        /// Amount to transfer
+        /// Amount to transfer: an integer of base units, or a decimal number of VSL tokens, like `1.5`
         #[arg(short, long)]
         amount: String,
         /// URL to connect to, or name of a known network
         #[arg(short, long, default_value = None)]
         network: Option<String>,
+        #[command(flatten)]
+        signing: SignOnlyArgs,
+    },
+
+    /// Transfer funds to many accounts, listed in a file. If some transfers fail, run the
+    /// command again: the transferred rows are skipped.
+    #[command(name = "pay:batch")]
+    PayBatch {
+        /// The file with the `recipient,amount` lines (CSV), or with an array of
+        /// `{"to": .., "amount": ..}` objects (JSON). The recipients are names or addresses.
+        #[arg(short, long)]
+        file: String,
+        /// The file with the result of every row, it is read to skip the transferred rows, when
+        /// the batch is run again. By default it is the batch file with the `.report.json` suffix.
+        #[arg(long, default_value = None)]
+        report: Option<String>,
+        /// URL to connect to, or name of a known network
+        #[arg(short, long, default_value = None)]
+        network: Option<String>,
     },
 
     #[command(subcommand_help_heading = "Account management commands")]
@@ -120,6 +333,12 @@ pub enum Commands {
         /// Overwrite the existing account
         #[arg(short, long, default_value_t = false)]
         overwrite: bool,
+        /// Store the private key as is, without encryption. Only for the devnet purposes.
+        #[arg(long, default_value_t = false)]
+        unencrypted: bool,
+        /// Generate a BIP-39 mnemonic phrase and derive the account private key from it.
+        #[arg(short, long, default_value_t = false)]
+        mnemonic: bool,
     },
     /// Makes use of an existing account with a provided private key.
     #[command(name = "account:load")]
@@ -129,9 +348,66 @@ pub enum Commands {
         /// Account private key. May be a private key itself, or a path to a file with private key.
         #[arg(short, long, default_value = None)]
         private_key: Option<String>,
+        /// BIP-39 mnemonic phrase, from which the account private key is derived.
+        #[arg(short, long, default_value = None, conflicts_with = "private_key")]
+        mnemonic: Option<String>,
+        /// The account index in the BIP-44 derivation path `m/44'/60'/0'/0/<index>`.
+        #[arg(short, long, default_value_t = 0, requires = "mnemonic")]
+        index: u32,
         /// Overwrite the existing account
         #[arg(short, long, default_value_t = false)]
         overwrite: bool,
+        /// Store the private key as is, without encryption. Only for the devnet purposes.
+        #[arg(long, default_value_t = false)]
+        unencrypted: bool,
+    },
+    /// Makes use of an account, which private key is held by an external signer process.
+    #[command(name = "account:external")]
+    AccountExternal {
+        /// Account name
+        name: String,
+        /// The address of the account
+        #[arg(short, long)]
+        address: String,
+        /// The signer command line. The signer gets `{"address": .., "hash": ..}` JSON on stdin and prints `{"signature": ..}` JSON to stdout.
+        #[arg(short, long)]
+        command: String,
+        /// Overwrite the existing account
+        #[arg(short, long, default_value_t = false)]
+        overwrite: bool,
+    },
+    /// Makes use of an account, which private key is held by a remote signing service.
+    #[command(name = "account:remote")]
+    AccountRemote {
+        /// Account name
+        name: String,
+        /// The address of the account
+        #[arg(short, long)]
+        address: String,
+        /// The URL of the signing service. It's called with the JSON-RPC method `signer_signHash`.
+        #[arg(short, long)]
+        url: String,
+        /// Overwrite the existing account
+        #[arg(short, long, default_value_t = false)]
+        overwrite: bool,
+    },
+    /// Makes use of an account without a private key. Such account is used only for reading.
+    #[command(name = "account:watch")]
+    AccountWatch {
+        /// Account name
+        name: String,
+        /// The address of the account
+        address: String,
+        /// Overwrite the existing account
+        #[arg(short, long, default_value_t = false)]
+        overwrite: bool,
+    },
+    /// Encrypts the private keys of accounts, which are stored unencrypted.
+    #[command(name = "account:encrypt")]
+    AccountEncrypt {
+        /// Account name, optional. If ommited, all unencrypted accounts are encrypted.
+        #[arg(default_value = None)]
+        name: Option<String>,
     },
     /// Exports the accounts private key.
     #[command(name = "account:export")]
@@ -142,6 +418,21 @@ pub enum Commands {
         /// The target file, where the private key would be written, optional. Otherwise, the private key will be shown in console.
         #[arg(short, long, default_value = "")]
         file: String,
+        /// The target file, where the encrypted Ethereum V3 keystore would be written, optional.
+        #[arg(short, long, default_value = "", conflicts_with = "file")]
+        keystore: String,
+    },
+    /// Imports an account from an encrypted Ethereum V3 keystore file.
+    #[command(name = "account:import")]
+    AccountImport {
+        /// Account name
+        name: String,
+        /// Path to the Ethereum V3 keystore file (i.e. created by geth or foundry)
+        #[arg(short, long)]
+        keystore: String,
+        /// Overwrite the existing account
+        #[arg(short, long, default_value_t = false)]
+        overwrite: bool,
     },
     /// Fetches the information about account.
     #[command(name = "account:get")]
@@ -162,6 +453,9 @@ pub enum Commands {
         /// URL to connect to, or name of a known network
         #[arg(short, long, default_value = None)]
         network: Option<String>,
+        /// Print the balance in base units, as it is returned by the node
+        #[arg(long, default_value_t = false)]
+        raw: bool,
     },
     /// Ask for the state of an account
     #[command(name = "account:state-get")]
@@ -179,8 +473,29 @@ pub enum Commands {
         /// Account in the form of hex string
         #[arg(short, long, default_value = None)]
         account: Option<String>,
-        /// The new account state, hex string
+        /// The new account state, hex string. `@<path>` reads the state from a file, `-` from stdin
         state: String,
+        /// The encoding of the state: `raw` (UTF-8 text as is), `base64` or `hex`
+        #[arg(long, value_enum, default_value_t = Encoding::Raw)]
+        encoding: Encoding,
+        /// The maximal size of the state in bytes, after encoding
+        #[arg(long, default_value_t = DEFAULT_MAX_INPUT_SIZE)]
+        max_size: usize,
+        /// URL to connect to, or name of a known network
+        #[arg(short, long, default_value = None)]
+        network: Option<String>,
+        #[command(flatten)]
+        signing: SignOnlyArgs,
+    },
+    /// Show the next nonce of an account: the cached one, or the one requested from the network
+    #[command(name = "account:nonce")]
+    AccountNonce {
+        /// Account name or address. By default the current account is used.
+        #[arg(short, long, default_value = None)]
+        account: Option<String>,
+        /// Drop the cached nonce and request it from the network again
+        #[arg(long, default_value_t = false)]
+        reset: bool,
         /// URL to connect to, or name of a known network
         #[arg(short, long, default_value = None)]
         network: Option<String>,
@@ -210,6 +525,51 @@ pub enum Commands {
         #[arg(long, help = "Display data in a table structure.", default_value_t = false, action = ArgAction::SetTrue)]
         table: bool,
     },
+    /// Manages the verifiers, to whom the claims of the account are addressed.
+    #[command(name = "account:verifiers")]
+    AccountVerifiers {
+        /// The action: add, remove or list
+        #[arg(value_enum)]
+        action: VerifiersAction,
+        /// The address or the name of a verifier. Required for `add` and `remove`.
+        #[arg(default_value = None, required_if_eq_any = [("action", "add"), ("action", "remove")])]
+        verifier: Option<String>,
+        /// Account name. By default the current account is used.
+        #[arg(short, long, default_value = None)]
+        account: Option<String>,
+    },
+    /// Sets the minimum number of verifier signatures for the claims of the account.
+    #[command(name = "account:quorum")]
+    AccountQuorum {
+        /// The quorum. Must not exceed the number of verifiers.
+        quorum: u16,
+        /// Account name. By default the current account is used.
+        #[arg(short, long, default_value = None)]
+        account: Option<String>,
+    },
+    /// Signs a message with the account (EIP-191 personal message signature).
+    #[command(name = "account:sign")]
+    AccountSign {
+        /// The message to sign. If it starts with `@`, the rest is a path to a file with the message.
+        #[arg(short, long)]
+        message: String,
+        /// Account name. By default the current account is used.
+        #[arg(short, long, default_value = None)]
+        account: Option<String>,
+    },
+    /// Verifies that a message is signed by an address (EIP-191 personal message signature).
+    #[command(name = "account:verify")]
+    AccountVerify {
+        /// The address or the name of the expected signer
+        #[arg(short, long)]
+        address: String,
+        /// The signature, hex string
+        #[arg(short, long)]
+        signature: String,
+        /// The signed message. If it starts with `@`, the rest is a path to a file with the message.
+        #[arg(short, long)]
+        message: String,
+    },
     /// Delete the account.
     #[command(name = "account:remove")]
     AccountRemove {
@@ -229,6 +589,9 @@ pub enum Commands {
         /// URL to connect to, or name of a known network
         #[arg(short, long, default_value = None)]
         network: Option<String>,
+        /// Print the balance in base units, as it is returned by the node
+        #[arg(long, default_value_t = false)]
+        raw: bool,
     },
     /// Ask for the balance of all assets for the account
     #[command(name = "asset:balances")]
@@ -239,6 +602,9 @@ pub enum Commands {
         /// URL to connect to, or name of a known network
         #[arg(short, long, default_value = None)]
         network: Option<String>,
+        /// Print the balances in base units, as they are returned by the node
+        #[arg(long, default_value_t = false)]
+        raw: bool,
     },
     /// Creates a new native asset.
     #[command(name = "asset:create")]
@@ -249,12 +615,15 @@ pub enum Commands {
         /// Number of decimals used for this asset
         #[arg(long, default_value = "18")]
         decimals: String,
-        /// Total number of tokens that exist
+        /// Total number of tokens that exist: an integer of base units, or a decimal number
+        /// of whole tokens, like `1000.0`
         #[arg(long)]
         supply: String,
         /// URL to connect to, or name of a known network
         #[arg(short, long, default_value = None)]
         network: Option<String>,
+        #[command(flatten)]
+        signing: SignOnlyArgs,
     },
     /// The transfer of an asset.
     #[command(name = "asset:transfer")]
@@ -265,12 +634,33 @@ pub enum Commands {
         /// Account name
         #[arg(long)]
         to: String,
+        /// Amount to transfer: an integer of base units, or a decimal number of whole tokens, like `1.5`
         #[arg(long)]
-        /// Total number of tokens that exist
         amount: String,
         /// URL to connect to, or name of a known network
         #[arg(short, long, default_value = None)]
         network: Option<String>,
+        #[command(flatten)]
+        signing: SignOnlyArgs,
+    },
+    /// The transfer of an asset to many accounts, listed in a file. If some transfers fail,
+    /// run the command again: the transferred rows are skipped.
+    #[command(name = "asset:transfer:batch")]
+    AssetTransferBatch {
+        /// Name of the asset
+        #[arg(long)]
+        asset: String,
+        /// The file with the `recipient,amount` lines (CSV), or with an array of
+        /// `{"to": .., "amount": ..}` objects (JSON). The recipients are names or addresses.
+        #[arg(short, long)]
+        file: String,
+        /// The file with the result of every row, it is read to skip the transferred rows, when
+        /// the batch is run again. By default it is the batch file with the `.report.json` suffix.
+        #[arg(long, default_value = None)]
+        report: Option<String>,
+        /// URL to connect to, or name of a known network
+        #[arg(short, long, default_value = None)]
+        network: Option<String>,
     },
     /// Get the information about an asset.
     #[command(name = "asset:get")]
@@ -282,6 +672,115 @@ pub enum Commands {
         network: Option<String>,
     },
 
+    /// Call a JSON-RPC method of a node with arbitrary parameters and print the raw response
+    #[command(name = "rpc:call")]
+    RpcCall {
+        /// The method to call, i.e. `vsl_getHealth`
+        method: String,
+        /// A parameter of the call: `key=value`. The value is parsed as JSON, if possible,
+        /// otherwise it is a string. May be repeated.
+        #[arg(short, long = "param")]
+        params: Vec<String>,
+        /// The parameters of the call as a JSON object, the `--param` values are added to it
+        #[arg(long, default_value = None)]
+        params_json: Option<String>,
+        /// Sign the message in the parameter `--sign-param` with the account
+        #[arg(long, default_value = None, requires = "sign_param")]
+        sign_as: Option<String>,
+        /// The parameter, which holds the message to sign with the `--sign-as` account
+        #[arg(long, default_value = None, requires = "sign_as")]
+        sign_param: Option<String>,
+        /// URL to connect to, or name of a known network
+        #[arg(short, long, default_value = None)]
+        network: Option<String>,
+    },
+
+    #[command(subcommand_help_heading = "Transaction commands")]
+    /// Send a message, signed by a command with `--sign-only`, to the network
+    #[command(name = "tx:send")]
+    TxSend {
+        /// The file with the signed message
+        file: String,
+        /// URL to connect to, or name of a known network
+        #[arg(short, long, default_value = None)]
+        network: Option<String>,
+    },
+
+    /// Decode a signed message: print its fields, the signer, and whether the signature is valid
+    #[command(name = "tx:decode")]
+    TxDecode {
+        /// The file with the signed message, or the signed message JSON itself
+        input: String,
+    },
+
+    #[command(subcommand_help_heading = "Address book commands")]
+    /// Name an address. If the address is already known by another name, it is renamed.
+    #[command(name = "address:add")]
+    AddressAdd {
+        /// The name of the address. A name may refer to several addresses.
+        name: String,
+        /// The address, 0x-prefixed hex string of 20 bytes
+        address: String,
+    },
+    /// List all known addresses
+    #[command(name = "address:list")]
+    AddressList {
+        #[arg(long, help = "Display data in a json structure.", default_value_t = false, action = ArgAction::SetTrue)]
+        json: bool,
+        #[arg(long, help = "Display data in a table structure.", default_value_t = false, action = ArgAction::SetTrue)]
+        table: bool,
+    },
+    /// Remove a name from the address book
+    #[command(name = "address:remove")]
+    AddressRemove {
+        /// The name to remove
+        name: String,
+        /// Remove only this address of the name, keeping the others
+        #[arg(short, long, default_value = None)]
+        address: Option<String>,
+    },
+    /// Show the addresses of a name, or the name of an address
+    #[command(name = "address:show")]
+    AddressShow {
+        /// The name or the address
+        name: String,
+        #[arg(long, help = "Display data in a json structure.", default_value_t = false, action = ArgAction::SetTrue)]
+        json: bool,
+    },
+    /// Name an identifier. If the identifier is already known by another name, it is renamed.
+    #[command(name = "id:add")]
+    IdAdd {
+        /// The name of the identifier. A name may refer to several identifiers.
+        name: String,
+        /// The identifier, hex string of 32 bytes, optionally 0x-prefixed
+        id: String,
+    },
+    /// List all known identifiers
+    #[command(name = "id:list")]
+    IdList {
+        #[arg(long, help = "Display data in a json structure.", default_value_t = false, action = ArgAction::SetTrue)]
+        json: bool,
+        #[arg(long, help = "Display data in a table structure.", default_value_t = false, action = ArgAction::SetTrue)]
+        table: bool,
+    },
+    /// Remove a name from the identifier book
+    #[command(name = "id:remove")]
+    IdRemove {
+        /// The name to remove
+        name: String,
+        /// Remove only this identifier of the name, keeping the others
+        #[arg(short, long, default_value = None)]
+        id: Option<String>,
+    },
+    /// Show the identifiers of a name, or the name of an identifier
+    #[command(name = "id:show")]
+    IdShow {
+        /// The name or the identifier
+        name: String,
+        #[arg(long, help = "Display data in a json structure.", default_value_t = false, action = ArgAction::SetTrue)]
+        json: bool,
+    },
+
     /// Request the health info about a node
     #[command(name = "health:check")]
     HealthCheck {
@@ -1,8 +1,15 @@
 use crate::accounts::Account;
 use crate::accounts::Accounts;
 use crate::accounts::Credentials;
+use crate::accounts::Derivation;
+use crate::claims::ClaimType;
+use crate::claims::builtin_claim_type;
+use crate::claims::builtin_claim_types;
 use crate::networks::Network;
 use crate::networks::Networks;
+use crate::nonces::Nonces;
+use crate::nonces::nonces_file;
+use crate::output::OutputFormat;
 
 use anyhow::Context;
 use anyhow::Result;
@@ -11,6 +18,7 @@ use dirs::config_dir;
 use regex::Regex;
 use serde::Deserialize;
 use serde::Serialize;
+use serde_json::Value;
 use std::collections::HashMap;
 use std::fs::File;
 use std::fs::OpenOptions;
@@ -69,13 +77,29 @@ pub struct Config {
     addresses: HexMap,
     /// Collection of known VSL identifiers
     identifiers: HexMap,
-    /// Collection of submitted, but not yet settled claims
+    /// Collection of submitted, but not yet settled claims by their ids
     pub submitted: HashMap<String, SubmittedClaim>,
     /// If a local server was started via `vsl-cli`, the info about it is stored here.
     pub server: Option<RpcServerLocal>,
+    /// The cached next nonces of the accounts of a config without a file. The nonces of a config
+    /// with a file are kept in their own file, see `nonces_file`.
+    #[serde(skip)]
+    nonces: Nonces,
+    /// The registered claim types by their names
+    #[serde(default)]
+    claim_types: HashMap<String, ClaimType>,
     /// The flag of being in REPL mode
     #[serde(skip, default = "default_mode")]
     pub mode: CliMode,
+    /// The format of the command output, set with `--output`
+    #[serde(skip)]
+    pub output: OutputFormat,
+    /// Build and sign the requests, which change the state, but do not send them, set with `--dry-run`
+    #[serde(skip)]
+    pub dry_run: bool,
+    /// The filter of the command output, set with `--query`
+    #[serde(skip)]
+    pub query: Option<String>,
 }
 
 fn default_mode() -> CliMode {
@@ -211,6 +235,8 @@ impl Configs {
                     ))
                 } else {
                     std::fs::remove_file(path);
+                    // The nonce cache may not exist
+                    let _ = std::fs::remove_file(nonces_file(path));
                     match &configs.current {
                         Some(current) => {
                             if *current == name {
@@ -247,12 +273,27 @@ impl Config {
             identifiers: HexMap::new("(0x)?", 64),
             submitted: HashMap::default(),
             server: None,
+            nonces: Nonces::default(),
+            claim_types: HashMap::default(),
             mode: mode,
+            output: OutputFormat::default(),
+            dry_run: false,
+            query: None,
         }
     }
 
+    /// Must the commands return their data rather than a formatted text: with a structured
+    /// `--output`, or with a `--query`, which filters the data.
+    pub fn structured(&self) -> bool {
+        self.output.is_structured() || self.query.is_some()
+    }
+
     /// Save current config to the persistent storage
     pub fn save(&self) -> Result<()> {
+        // The dry run does not change the persisted state
+        if self.dry_run {
+            return Ok(());
+        }
         // In case the config is temporary, it is not saved.
         match &self.file {
             Some(path) => {
@@ -340,15 +381,34 @@ impl Config {
     pub fn generate_credentials(&self, private_key_opt: Option<String>) -> Result<Credentials> {
         self.accounts.generate_credentials(private_key_opt)
     }
+    /// Generates the credentials from an encrypted V3 keystore and checks if the address is not used twice.
+    pub fn keystore_credentials(&self, keystore: Value, password: &str) -> Result<Credentials> {
+        self.accounts.keystore_credentials(keystore, password)
+    }
+    /// Generates the credentials, which delegate signing to an external signer process or
+    /// a remote signing service.
+    pub fn external_credentials(
+        &self,
+        address: &str,
+        command: Option<Vec<String>>,
+        url: Option<String>,
+    ) -> Result<Credentials> {
+        self.accounts.external_credentials(address, command, url)
+    }
+    /// Generates the credentials without a private key, i.e. for a watch-only account.
+    pub fn watch_credentials(&self, address: &str) -> Result<Credentials> {
+        self.accounts.watch_credentials(address)
+    }
     /// Creates a new account with label `name`.
     pub fn create_account(
         &mut self,
         name: String,
         credentials: Credentials,
+        derivation: Option<Derivation>,
         overwrite: bool,
     ) -> Result<Account> {
         self.accounts
-            .create(name.clone(), credentials, overwrite)
+            .create(name.clone(), credentials, derivation, overwrite)
             .and_then(|account| {
                 self.addresses
                     .add_id(&name, account.credentials.address.clone())?;
@@ -364,6 +424,22 @@ impl Config {
     pub fn use_account(&mut self, nm: &str) -> Result<()> {
         self.accounts.set_using(nm).and_then(|_| self.save())
     }
+    /// Encrypts the private key of the account with label `name`.
+    pub fn encrypt_account(&mut self, name: &str, password: &str) -> Result<()> {
+        self.accounts
+            .encrypt(name, password)
+            .and_then(|_| self.save())
+    }
+    /// Applies the `update` to the known account with label `name` and saves the config.
+    pub fn update_account(
+        &mut self,
+        name: Option<&str>,
+        update: impl FnOnce(&mut Account) -> Result<()>,
+    ) -> Result<Account> {
+        let account = self.accounts.update(name, update)?;
+        self.save()?;
+        Ok(account)
+    }
     /// Deletes the known account with label `name`.
     pub fn remove_account(&mut self, name: &str) -> Result<()> {
         self.addresses.remove_id(name)?;
@@ -390,37 +466,265 @@ impl Config {
     pub fn lookup_identifier(&mut self, name: &str) -> Result<String> {
         self.identifiers.lookup_id(name)
     }
+    /// The book of known addresses
+    pub fn address_book(&self) -> &HexMap {
+        &self.addresses
+    }
+    /// The book of known identifiers
+    pub fn identifier_book(&self) -> &HexMap {
+        &self.identifiers
+    }
+    /// Names the `address` with `name`, the previous name of the address is returned.
+    /// The addresses of accounts keep their names.
+    pub fn name_address(&mut self, name: &str, address: String) -> Result<Option<String>> {
+        if let Some(previous) = self.addresses.lookup_name(&address).cloned() {
+            if previous != name && self.accounts.get(Some(&previous)).is_ok() {
+                return Err(anyhow::anyhow!(
+                    "'{}' is the address of the account '{}', it can't be renamed",
+                    address,
+                    previous
+                ));
+            }
+        }
+        let previous = self.addresses.set_name(name, address)?;
+        self.save()?;
+        Ok(previous)
+    }
+    /// Names the `identifier` with `name`, the previous name of the identifier is returned.
+    pub fn name_identifier(&mut self, name: &str, identifier: String) -> Result<Option<String>> {
+        let previous = self.identifiers.set_name(name, identifier)?;
+        self.save()?;
+        Ok(previous)
+    }
+    /// Removes the `name` from the address book, or only its single `address`.
+    /// The names of accounts are kept: they are removed along with the account.
+    pub fn remove_address(&mut self, name: &str, address: Option<&str>) -> Result<()> {
+        if self.accounts.get(Some(name)).is_ok() {
+            return Err(anyhow::anyhow!(
+                "'{}' is an account, use `account:remove` to remove it",
+                name
+            ));
+        }
+        match address {
+            Some(address) => self.addresses.remove_one_id(name, address)?,
+            None => self.addresses.remove_id(name)?,
+        }
+        self.save()
+    }
+    /// Removes the `name` from the identifier book, or only its single `identifier`.
+    pub fn remove_identifier(&mut self, name: &str, identifier: Option<&str>) -> Result<()> {
+        match identifier {
+            Some(identifier) => self.identifiers.remove_one_id(name, identifier)?,
+            None => self.identifiers.remove_id(name)?,
+        }
+        self.save()
+    }
 
-    /// Add the submitted claim
+    /// Add the submitted claim, it is stored once, by its id
     pub fn add_claim(&mut self, submitted: SubmittedClaim, claim_id: String) -> Result<()> {
-        let claim = submitted.claim.clone();
-        if self.submitted.contains_key(&claim) || self.submitted.contains_key(&claim_id) {
-            return Err(anyhow::anyhow!("claim '{}' is already submtted", claim));
+        if self.has_claim(&submitted.claim) || self.submitted.contains_key(&claim_id) {
+            return Err(anyhow::anyhow!(
+                "claim '{}' is already submtted",
+                submitted.claim
+            ));
         }
-        self.submitted.insert(claim.clone(), submitted.clone());
-        self.submitted.insert(claim_id.clone(), submitted);
+        self.submitted.insert(claim_id, submitted);
         self.save()
     }
-    /// Try to find the submitted claim
+    /// Try to find the submitted claim by its id, or by its text
     pub fn get_claim(&mut self, claim: &str) -> Result<&SubmittedClaim> {
         self.submitted
             .get(claim)
+            .or_else(|| {
+                self.submitted
+                    .values()
+                    .find(|submitted| submitted.claim == claim)
+            })
             .ok_or(anyhow::anyhow!("Claim '{}' was not submitted.", claim))
     }
     /// Check the claim was submitted
     pub fn has_claim(&mut self, claim: &str) -> bool {
-        self.submitted.contains_key(claim)
+        self.get_claim(claim).is_ok()
     }
-    /// Try to find the submitted claim
+    /// Removes the submitted claim: its entry by the claim id, and the entry by the claim text,
+    /// which the earlier versions have stored too
     pub fn remove_claim(&mut self, claim: &str) -> Result<()> {
-        match self.submitted.remove(claim) {
-            Some(_) => {
-                self.save()?;
-                Ok(())
+        match self.get_claim(claim).ok().cloned() {
+            Some(removed) => {
+                self.submitted.retain(|_, submitted| {
+                    submitted.claim != removed.claim || submitted.nonce != removed.nonce
+                });
+                self.save()
             }
             None => Err(anyhow::anyhow!("'{}' is not present", claim)),
         }
     }
+    /// The submitted claims with their ids, one entry per claim, sorted by the expiration time
+    pub fn submitted_claims(&self) -> Vec<(Option<String>, SubmittedClaim)> {
+        let mut claims: Vec<(Option<String>, SubmittedClaim)> = Vec::new();
+        for (key, submitted) in &self.submitted {
+            let id = if *key == submitted.claim {
+                None
+            } else {
+                Some(key.clone())
+            };
+            let known = claims.iter_mut().find(|(_, known)| {
+                known.claim == submitted.claim && known.nonce == submitted.nonce
+            });
+            match known {
+                Some((known_id, _)) => *known_id = known_id.take().or(id),
+                None => claims.push((id, submitted.clone())),
+            }
+        }
+        claims.sort_by(|(_, a), (_, b)| {
+            (a.expires.seconds(), &a.claim).cmp(&(b.expires.seconds(), &b.claim))
+        });
+        claims
+    }
+    /// Removes the submitted claims, which expire before the timestamp. Returns the number of them.
+    pub fn prune_claims(&mut self, before: u64) -> Result<usize> {
+        let expired: Vec<String> = self
+            .submitted_claims()
+            .into_iter()
+            .filter(|(_, submitted)| submitted.expires.seconds() < before)
+            .map(|(_, submitted)| submitted.claim)
+            .collect();
+        if expired.is_empty() {
+            return Ok(0);
+        }
+        let count = expired.len();
+        self.submitted
+            .retain(|_, submitted| !expired.contains(&submitted.claim));
+        self.save()?;
+        Ok(count)
+    }
+
+    /// Changes the cached nonces: in the nonce cache file, or in memory for a config without
+    /// a file, or in the dry run.
+    fn update_nonces<R, E: From<anyhow::Error>>(
+        &mut self,
+        change: impl FnOnce(&mut Nonces) -> Result<R, E>,
+    ) -> Result<R, E> {
+        match &self.file {
+            Some(file) if !self.dry_run => Nonces::update(&nonces_file(file), change),
+            _ => change(&mut self.nonces),
+        }
+    }
+    /// Returns the cached next nonce of the `address` in the `network`.
+    pub fn cached_nonce(&self, network: &Network, address: &str) -> Result<Option<u64>> {
+        match &self.file {
+            Some(file) => Ok(Nonces::read(&nonces_file(file))?.get(network, address)),
+            None => Ok(self.nonces.get(network, address)),
+        }
+    }
+    /// Reserves the next nonce of the `address` in the `network`: the cached one, or the one
+    /// `fetch`-ed from the network. The cache is advanced, so that a concurrent command takes
+    /// the following nonce. The network is requested before the cache is locked, so that a slow
+    /// node does not hold the lock: the later of the fetched and the cached nonces is reserved then.
+    pub fn reserve_nonce<E: From<anyhow::Error>>(
+        &mut self,
+        network: &Network,
+        address: &str,
+        mut fetch: impl FnMut() -> Result<u64, E>,
+    ) -> Result<u64, E> {
+        let mut fetched = match self.cached_nonce(network, address)? {
+            Some(_) => None,
+            None => Some(fetch()?),
+        };
+        loop {
+            let reserved = self.update_nonces(|nonces| -> Result<Option<u64>, E> {
+                let nonce = match (nonces.get(network, address), fetched) {
+                    (Some(cached), fetched) => cached.max(fetched.unwrap_or(0)),
+                    (None, Some(fetched)) => fetched,
+                    // The cached nonce is dropped meanwhile, it is fetched without the lock
+                    (None, None) => return Ok(None),
+                };
+                nonces.set(network, address, nonce + 1);
+                Ok(Some(nonce))
+            })?;
+            match reserved {
+                Some(nonce) => return Ok(nonce),
+                None => fetched = Some(fetch()?),
+            }
+        }
+    }
+    /// Caches the next nonce of the `address` in the `network`, unless a later one is cached.
+    pub fn advance_nonce(&mut self, network: &Network, address: &str, next: u64) -> Result<()> {
+        self.update_nonces(|nonces| -> Result<()> {
+            if nonces
+                .get(network, address)
+                .is_none_or(|cached| cached < next)
+            {
+                nonces.set(network, address, next);
+            }
+            Ok(())
+        })
+    }
+    /// Drops the cached nonce of the `address` in the `network`, so that it is requested again.
+    pub fn reset_nonce(&mut self, network: &Network, address: &str) -> Result<Option<u64>> {
+        self.update_nonces(|nonces| -> Result<Option<u64>> { Ok(nonces.reset(network, address)) })
+    }
+
+    /// Drops the cached nonces of all accounts in the `network`.
+    pub fn reset_network_nonces(&mut self, network: &Network) -> Result<()> {
+        self.update_nonces(|nonces| -> Result<()> {
+            nonces.reset_network(network);
+            Ok(())
+        })
+    }
+
+    /// Registers a claim type. An existing type is replaced only with `overwrite`,
+    /// a built-in type is never replaced.
+    pub fn add_claim_type(
+        &mut self,
+        name: &str,
+        claim_type: ClaimType,
+        overwrite: bool,
+    ) -> Result<()> {
+        if builtin_claim_type(name).is_some() {
+            return Err(anyhow!("Claim type '{}' is built-in", name));
+        }
+        if self.claim_types.contains_key(name) && !overwrite {
+            return Err(anyhow!(
+                "Claim type '{}' already exists, use --overwrite to replace it",
+                name
+            ));
+        }
+        self.claim_types.insert(name.to_string(), claim_type);
+        self.save()
+    }
+    /// The built-in or registered claim type of the `name`
+    pub fn get_claim_type(&self, name: &str) -> Option<ClaimType> {
+        builtin_claim_type(name).or_else(|| self.claim_types.get(name).cloned())
+    }
+    /// All built-in and registered claim types, sorted by their names. The flag tells
+    /// if a type is built-in.
+    pub fn claim_types(&self) -> Vec<(String, ClaimType, bool)> {
+        let mut types: Vec<(String, ClaimType, bool)> = builtin_claim_types()
+            .into_iter()
+            .map(|(name, claim_type)| (name.to_string(), claim_type, true))
+            .chain(
+                self.claim_types
+                    .iter()
+                    .map(|(name, claim_type)| (name.clone(), claim_type.clone(), false)),
+            )
+            .collect();
+        types.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+        types
+    }
+    /// Removes a registered claim type
+    pub fn remove_claim_type(&mut self, name: &str) -> Result<()> {
+        if builtin_claim_type(name).is_some() {
+            return Err(anyhow!(
+                "Claim type '{}' is built-in, it can not be removed",
+                name
+            ));
+        }
+        match self.claim_types.remove(name) {
+            Some(_) => self.save(),
+            None => Err(anyhow!("Claim type '{}' is not registered", name)),
+        }
+    }
 
     /// Checks if the `address` is valid and converts it to the normal form
     pub fn make_valid_address(&self, address: &str) -> Result<String> {
@@ -527,6 +831,59 @@ impl HexMap {
             Some(_) => Ok(()),
         }
     }
+    /// Names the `id` with `name`. Unlike `add_id`, the id is moved from the name it was known
+    /// by before, if any. Returns that previous name.
+    pub fn set_name(&mut self, name: &str, id: String) -> Result<Option<String>> {
+        let id = self.check_hex_format(&id)?;
+        let previous = self.lookup_name(&id).cloned();
+        if let Some(previous) = &previous {
+            if previous == name {
+                return Ok(None);
+            }
+            self.remove_one_id(previous, &id)?;
+        }
+        self.add_id(name, id)?;
+        Ok(previous)
+    }
+    /// Lists all the names with their ids, sorted by name
+    pub fn list(&self) -> Vec<(&String, &Vec<String>)> {
+        let mut list: Vec<_> = self.str_to_id.iter().collect();
+        list.sort_by(|(name1, _), (name2, _)| name1.cmp(name2));
+        list
+    }
+    /// Returns all the ids associated with `name`, if it is known
+    pub fn ids(&self, name: &str) -> Option<&Vec<String>> {
+        self.str_to_id.get(name)
+    }
+    /// Finds the name of the `id`, if it is known
+    pub fn lookup_name(&self, id: &str) -> Option<&String> {
+        let id = id.to_ascii_lowercase();
+        self.str_to_id
+            .iter()
+            .find(|(_, ids)| ids.contains(&id))
+            .map(|(name, _)| name)
+    }
+    /// Removes a single `id` from the ids of `name`. The name is removed along with its last id.
+    pub fn remove_one_id(&mut self, name: &str, id: &str) -> Result<()> {
+        let id = id.to_ascii_lowercase();
+        let ids = self.str_to_id.get_mut(name).ok_or(anyhow::anyhow!(
+            "failed to remove id for the string '{}' - it's not present",
+            name
+        ))?;
+        let len = ids.len();
+        ids.retain(|known| *known != id);
+        if ids.len() == len {
+            return Err(anyhow::anyhow!(
+                "id '{}' is not associated with the string '{}'",
+                id,
+                name
+            ));
+        }
+        if ids.is_empty() {
+            self.str_to_id.remove(name);
+        }
+        Ok(())
+    }
 }
 
 /// The directory of persistent storage of `vsl-cli` application.
@@ -1,11 +1,42 @@
 #![allow(unused)]
 
-use crate::accounts::private_key_to_signer;
+use crate::accounts::Account;
+use crate::accounts::derive_private_key;
+use crate::accounts::generate_mnemonic;
+use crate::amounts::VSL_DECIMALS;
+use crate::amounts::amount_from_value;
+use crate::amounts::format_amount;
+use crate::amounts::parse_amount;
+use crate::amounts::to_hex as amount_to_hex;
+use crate::batch::RowResult;
+use crate::batch::RowStatus;
+use crate::batch::find_done;
+use crate::batch::read_report;
+use crate::batch::read_rows;
+use crate::batch::report_file;
+use crate::batch::write_report;
+use crate::claims::ClaimState;
+use crate::claims::ClaimType;
+use crate::claims::contains_string;
 use crate::commands::Commands;
+use crate::commands::SignOnlyArgs;
+use crate::commands::VerifiersAction;
 use crate::configs::Config;
 use crate::configs::Configs;
+use crate::configs::HexMap;
 use crate::configs::RpcServerInit;
+use crate::inputs::DEFAULT_MAX_INPUT_SIZE;
+use crate::inputs::check_single_stdin;
+use crate::inputs::read_input;
+use crate::inputs::read_text_input;
+use crate::keystore::read_new_password;
+use crate::keystore::read_password;
 use crate::networks::Network;
+use crate::nonces::is_nonce_error;
+use crate::output::OutputFormat;
+use crate::output::render;
+use crate::rpc_client::EXIT_CODE_CONNECTION_ERROR;
+use crate::rpc_client::EXIT_CODE_REJECTED;
 use crate::rpc_client::RpcClientError;
 use crate::rpc_client::RpcClientInterface;
 use crate::rpc_client::check_network_is_up;
@@ -15,7 +46,23 @@ use crate::rpc_server::dump_local_server;
 use crate::rpc_server::init_local_server;
 use crate::rpc_server::start_local_server;
 use crate::rpc_server::stop_local_server;
+use crate::schema::read_schema;
+use crate::times::expiration_time;
+use crate::times::humanize_timestamps;
+use crate::times::parse_duration;
+use crate::times::since_time;
+use crate::transactions::SignedTransaction;
+use crate::transactions::decode;
+use crate::verifier::ClaimRecord;
+use crate::verifier::POLL_OVERLAP;
+use crate::verifier::Verdict;
+use crate::verifier::VerifierOptions;
+use crate::verifier::VerifierState;
+use crate::verifier::state_file;
+use crate::verifier::submitted_claims;
 
+use alloy::primitives::Signature;
+use alloy::signers::SignerSync as _;
 use jsonrpsee::core::params::ObjectParams;
 use log::info;
 use serde_json::Value;
@@ -42,6 +89,36 @@ pub fn execute_command<T: RpcClientInterface>(
     config: &mut Config,
     command: &Commands,
     rpc_client: &mut T,
+) -> anyhow::Result<Value, RpcClientError> {
+    if !config.dry_run {
+        return execute(config, command, rpc_client);
+    }
+    // These commands change the state beyond the config, which the dry run cannot skip
+    let command_name = match command {
+        Commands::AccountExport { .. } => Some("account:export"),
+        Commands::ServerInit { .. } => Some("server:init"),
+        Commands::ServerStart { .. } => Some("server:start"),
+        Commands::ServerStop { .. } => Some("server:stop"),
+        Commands::ConfigCreate { .. } => Some("config:create"),
+        Commands::ConfigUse { .. } => Some("config:use"),
+        Commands::ConfigRempove { .. } => Some("config:remove"),
+        _ => None,
+    };
+    if let Some(command_name) = command_name {
+        return Err(RpcClientError::IncorrectRequest(format!(
+            "`{}` does not support --dry-run",
+            command_name
+        )));
+    }
+    // The changes of the config are dropped: neither saved, nor kept in the REPL session
+    let mut config = config.clone();
+    execute(&mut config, command, rpc_client)
+}
+
+fn execute<T: RpcClientInterface>(
+    config: &mut Config,
+    command: &Commands,
+    rpc_client: &mut T,
 ) -> anyhow::Result<Value, RpcClientError> {
     // You can check for the existence of subcommands, and if found use their
     // matches just as you would the top level cmd
@@ -54,8 +131,27 @@ pub fn execute_command<T: RpcClientInterface>(
             expires,
             lifetime,
             fee,
+            claim_encoding,
+            proof_encoding,
+            max_size,
+            signing,
         } => {
+            let incorrect = |err: anyhow::Error| RpcClientError::IncorrectRequest(err.to_string());
+            check_single_stdin(&[claim.as_str(), proof.as_str()], config.mode)
+                .map_err(incorrect)?;
+            // A claim, read from a file or stdin, is not named by its text in the identifier book
+            let inline = claim != "-" && !claim.starts_with('@');
+            let claim =
+                &read_text_input(claim, *claim_encoding, *max_size, "claim").map_err(incorrect)?;
+            let proof =
+                &read_text_input(proof, *proof_encoding, *max_size, "proof").map_err(incorrect)?;
+            // The malformed claims are rejected before paying the fee
+            if let Some(registered) = config.get_claim_type(claim_type) {
+                registered.check(claim, proof).map_err(incorrect)?;
+            }
             let now = Timestamp::now().seconds();
+            // The expired claims are forgotten, so that they may be submitted again
+            config.prune_claims(now)?;
             // Sanity checks
             if config.has_claim(claim) {
                 return Err(RpcClientError::GeneralError(format!(
@@ -63,61 +159,53 @@ pub fn execute_command<T: RpcClientInterface>(
                     claim
                 )));
             }
-            if expires.is_none() && lifetime.is_none() {
-                return Err(RpcClientError::GeneralError(format!(
-                    "Claim '{}' expiration value is not set: both `--expires` and `--lifetime` are not set",
-                    claim
-                )));
-            } else {
-                if expires.is_some() && lifetime.is_some() {
-                    return Err(RpcClientError::GeneralError(format!(
-                        "Claim '{}' expiration value is set ambiguously: both `--expires` and `--lifetime` are set",
-                        claim
-                    )));
-                } else {
-                    match expires {
-                        Some(timestamp) => {
-                            if *timestamp <= now {
-                                return Err(RpcClientError::GeneralError(format!(
-                                    "Claim '{}' expiration time {} has already passed",
-                                    claim, now
-                                )));
-                            }
-                        }
-                        None => {}
-                    }
-                }
-            }
+            let expires =
+                expiration_time(expires.as_deref(), lifetime.as_deref(), now).map_err(|err| {
+                    RpcClientError::IncorrectRequest(format!("Claim '{}': {}", claim, err))
+                })?;
             info!("Submitting claim json: '{}', fee: {}", claim, fee);
             let account = config.get_account(None)?;
             let network = config.get_network(network.clone())?;
-            let nonce = rpc_client.get_nonce(network.clone(), &account.credentials.address)?;
-            let expires = expires.unwrap_or(now + lifetime.unwrap());
-            let to_submit: SubmittedClaim = SubmittedClaim {
-                claim: claim.clone(),
-                claim_type: claim_type.clone(),
-                proof: proof.clone(),
-                nonce: nonce.to_string(),
-                to: account
-                    .signatures
-                    .iter()
-                    .map(|addr| VslAddress::from_str(addr).unwrap())
-                    .collect(),
-                quorum: account.quorum,
-                from: VslAddress::from_str(&account.credentials.address).unwrap(),
-                expires: Timestamp::from_seconds(expires),
-                fee: to_hex(fee)?,
+            let address = &account.credentials.address;
+            let to_submit = |nonce: u64| -> Result<SubmittedClaim, RpcClientError> {
+                Ok(SubmittedClaim {
+                    claim: claim.clone(),
+                    claim_type: claim_type.clone(),
+                    proof: proof.clone(),
+                    nonce: nonce.to_string(),
+                    to: account
+                        .signatures
+                        .iter()
+                        .map(|addr| VslAddress::from_str(addr).unwrap())
+                        .collect(),
+                    quorum: account.quorum,
+                    from: VslAddress::from_str(address).unwrap(),
+                    expires: Timestamp::from_seconds(expires),
+                    fee: to_hex(fee, VSL_DECIMALS)?,
+                })
             };
-            let mut params = ObjectParams::new();
-            let message_signed = to_submit
-                .clone()
-                .into_signed(&private_key_to_signer(&account.credentials.private_key))?;
-            params.insert("claim", message_signed);
-            let response = rpc_client.make_request(network, "vsl_submitClaim", params)?;
+            let sign = |nonce: u64| -> Result<SignedTransaction, RpcClientError> {
+                let message_signed =
+                    to_submit(nonce)?.into_signed(&account.credentials.signer()?)?;
+                Ok(SignedTransaction::new(
+                    "vsl_submitClaim",
+                    "claim",
+                    serde_json::to_value(message_signed)?,
+                ))
+            };
+            if let Some(output) =
+                unsent_output(config, rpc_client, &network, address, signing, &sign)?
+            {
+                return Ok(output);
+            }
+            let (response, nonce) =
+                send_signed(config, rpc_client, &network, address, &signing.nonce, &sign)?;
             match response {
                 Value::String(ref claim_id) => {
-                    config.add_claim(to_submit, claim_id.clone())?;
-                    config.add_identifier(claim, claim_id.clone())?;
+                    config.add_claim(to_submit(nonce)?, claim_id.clone())?;
+                    if inline {
+                        config.add_identifier(claim, claim_id.clone())?;
+                    }
                     Ok(response)
                 }
                 _ => Err(RpcClientError::GeneralError(format!(
@@ -130,6 +218,7 @@ pub fn execute_command<T: RpcClientInterface>(
             network,
             claim,
             address,
+            signing,
         } => {
             info!("Settling claim: '{}'", claim);
             let account = config.get_account(None)?;
@@ -138,23 +227,27 @@ pub fn execute_command<T: RpcClientInterface>(
                 None => account.credentials.address.clone(),
             };
             let network = config.get_network(network.clone())?;
-            let nonce = rpc_client.get_nonce(network.clone(), &address)?;
             let submitted = config.get_claim(claim)?;
             let target_claim_id = VerifiedClaim::claim_id_hash(
                 &submitted.from.address,
                 &submitted.nonce,
                 &submitted.claim,
             );
-            let message = SettleClaimMessage {
-                from: VslAddress::from_str(&address).unwrap(),
-                nonce: nonce.to_string(),
-                target_claim_id: target_claim_id.to_string(),
-            };
-            let message_signed =
-                message.into_signed(&private_key_to_signer(&account.credentials.private_key))?;
-            let mut params = ObjectParams::new();
-            params.insert("settled_claim", message_signed);
-            let response = rpc_client.make_request(network, "vsl_settleClaim", params)?;
+            let sign =
+                |nonce: u64| settle_message(&account, &address, nonce, target_claim_id.to_string());
+            if let Some(output) =
+                unsent_output(config, rpc_client, &network, &address, signing, &sign)?
+            {
+                return Ok(output);
+            }
+            let (response, _) = send_signed(
+                config,
+                rpc_client,
+                &network,
+                &address,
+                &signing.nonce,
+                &sign,
+            )?;
             match response {
                 Value::String(ref claim_id) => {
                     config.remove_claim(claim)?;
@@ -172,38 +265,16 @@ pub fn execute_command<T: RpcClientInterface>(
             address,
             since,
             within,
+            raw,
         } => {
             let now = Timestamp::now().seconds();
-            if since.is_none() && within.is_none() {
-                return Err(RpcClientError::GeneralError(
-                    "When quering claim the since value is not set: both `--since` and `--within` are not set".to_string())
-                );
-            } else {
-                if since.is_some() && within.is_some() {
-                    return Err(RpcClientError::GeneralError(
-                        "since value is set ambiguously: both `--since` and `--within` are set"
-                            .to_string(),
-                    ));
-                } else {
-                    match since {
-                        Some(timestamp) => {
-                            if *timestamp > now {
-                                return Err(RpcClientError::GeneralError(format!(
-                                    "Since time {} is in the future, query makes no sense",
-                                    now
-                                )));
-                            }
-                        }
-                        None => {}
-                    }
-                }
-            }
+            let since = since_time(since.as_deref(), within.as_deref(), now)
+                .map_err(|err| RpcClientError::IncorrectRequest(err.to_string()))?;
             let account = config.get_account(None)?;
             let address = match address {
                 Some(address) => config.lookup_address(address)?,
                 None => account.credentials.address,
             };
-            let since = since.unwrap_or(now - within.unwrap());
             info!(
                 "Fetch verified claims targeted for a client address since a timestamp, address: '{}', since: {}",
                 address, since
@@ -211,49 +282,28 @@ pub fn execute_command<T: RpcClientInterface>(
             let mut params = ObjectParams::new();
             params.insert("address", address)?;
             params.insert("since", Timestamp::from_seconds(since))?;
-            rpc_client.make_request(
+            let claims = rpc_client.make_request(
                 config.get_network(network.clone())?,
                 "vsl_listSettledClaimsForReceiver",
                 params,
-            )
+            )?;
+            Ok(human_times(claims, *raw))
         }
         Commands::ClaimSubmitted {
             network,
             address,
             since,
             within,
+            raw,
         } => {
             let now = Timestamp::now().seconds();
-            if since.is_none() && within.is_none() {
-                return Err(RpcClientError::GeneralError(
-                    "When quering claim the since value is not set: both `--since` and `--within` are not set".to_string())
-                );
-            } else {
-                if since.is_some() && within.is_some() {
-                    return Err(RpcClientError::GeneralError(
-                        "since value is set ambiguously: both `--since` and `--within` are set"
-                            .to_string(),
-                    ));
-                } else {
-                    match since {
-                        Some(timestamp) => {
-                            if *timestamp > now {
-                                return Err(RpcClientError::GeneralError(format!(
-                                    "Since time {} is in the future, query makes no sense",
-                                    now
-                                )));
-                            }
-                        }
-                        None => {}
-                    }
-                }
-            }
+            let since = since_time(since.as_deref(), within.as_deref(), now)
+                .map_err(|err| RpcClientError::IncorrectRequest(err.to_string()))?;
             let account = config.get_account(None)?;
             let address = match address {
                 Some(address) => config.lookup_address(address)?,
                 None => account.credentials.address,
             };
-            let since = since.unwrap_or(now - within.unwrap());
             info!(
                 "Fetch verified claims targeted for a client address since a timestamp, address: '{}', since: {}",
                 address, since
@@ -261,43 +311,296 @@ pub fn execute_command<T: RpcClientInterface>(
             let mut params = ObjectParams::new();
             params.insert("address", address)?;
             params.insert("since", Timestamp::from_seconds(since))?;
-            rpc_client.make_request(
+            let claims = rpc_client.make_request(
                 config.get_network(network.clone())?,
                 "vsl_listSubmittedClaimsForReceiver",
                 params,
-            )
+            )?;
+            Ok(human_times(claims, *raw))
         }
-        Commands::ClaimGet { network, id } => {
+        Commands::ClaimGet { network, id, raw } => {
             info!("Getting settled claim with (claim_id): '{}'", id);
             let mut params = ObjectParams::new();
             let address = config.lookup_identifier(&id)?;
             params.insert("claim_id", address);
-            rpc_client.make_request(
+            let claim = rpc_client.make_request(
                 config.get_network(network.clone())?,
                 "vsl_getSettledClaimById",
                 params,
-            )
+            )?;
+            Ok(human_times(claim, *raw))
+        }
+        Commands::ClaimStatus {
+            claim,
+            network,
+            raw,
+        } => {
+            let network = config.get_network(network.clone())?;
+            let (_, status) = claim_status(config, rpc_client, &network, claim, 0)?;
+            Ok(human_times(status, *raw))
+        }
+        Commands::ClaimWait {
+            claim,
+            timeout,
+            interval,
+            network,
+            raw,
+        } => {
+            let timeout = parse_duration(timeout)
+                .map_err(|err| RpcClientError::IncorrectRequest(err.to_string()))?;
+            let interval = parse_duration(interval)
+                .map_err(|err| RpcClientError::IncorrectRequest(err.to_string()))?;
+            let network = config.get_network(network.clone())?;
+            let deadline = Timestamp::now().seconds().saturating_add(timeout);
+            let mut since = 0;
+            loop {
+                let polled = Timestamp::now().seconds();
+                let (state, status) = claim_status(config, rpc_client, &network, claim, since)?;
+                info!("Claim '{}' is {}", claim, state);
+                // The next polls only list the claims, settled since the previous poll
+                since = polled.saturating_sub(POLL_OVERLAP);
+                match state {
+                    ClaimState::Settled => return Ok(human_times(status, *raw)),
+                    ClaimState::Expired => {
+                        return Err(RpcClientError::GeneralError(format!(
+                            "Claim '{}' has expired before it was settled",
+                            claim
+                        )));
+                    }
+                    _ if Timestamp::now().seconds() >= deadline => {
+                        return Err(RpcClientError::GeneralError(format!(
+                            "Timed out waiting for claim '{}' to be settled, it is {}",
+                            claim, state
+                        )));
+                    }
+                    _ => std::thread::sleep(std::time::Duration::from_secs(interval.max(1))),
+                }
+            }
+        }
+        Commands::ClaimList {
+            expired,
+            pending,
+            raw,
+        } => {
+            let now = Timestamp::now().seconds();
+            let rows: Vec<Value> = config
+                .submitted_claims()
+                .into_iter()
+                .map(|(id, submitted)| submitted_row(id, &submitted, now))
+                .filter(|row| match (*expired, *pending) {
+                    (true, _) => row["status"] == json!(ClaimState::Expired),
+                    (_, true) => row["status"] == json!(ClaimState::Submitted),
+                    _ => true,
+                })
+                .collect();
+            if config.structured() {
+                Ok(human_times(Value::Array(rows), *raw))
+            } else {
+                let mut lines = vec!["Submitted claims:".to_string()];
+                if rows.is_empty() {
+                    lines.push("   No claims are present.".to_string());
+                }
+                for row in human_times(Value::Array(rows), *raw)
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                {
+                    lines.push(format!(
+                        "  {}  {}, expires {}",
+                        row["claim"].as_str().unwrap_or_default(),
+                        row["status"].as_str().unwrap_or_default(),
+                        row["expires"].to_string().trim_matches('"')
+                    ));
+                }
+                Ok(Value::String(lines.join("\n")))
+            }
+        }
+        Commands::ClaimShow { claim, raw } => {
+            let submitted = find_submitted(config, claim).ok_or(RpcClientError::GeneralError(
+                format!("Claim '{}' is not submitted, or is already settled", claim),
+            ))?;
+            let id = config
+                .submitted_claims()
+                .into_iter()
+                .find(|(_, known)| known.claim == submitted.claim)
+                .and_then(|(id, _)| id);
+            let mut row = submitted_row(id, &submitted, Timestamp::now().seconds());
+            row["proof"] = json!(submitted.proof);
+            row["from"] = serde_json::to_value(&submitted.from)?;
+            row["to"] = serde_json::to_value(&submitted.to)?;
+            row["quorum"] = json!(submitted.quorum);
+            Ok(human_times(row, *raw))
+        }
+        Commands::ClaimForget { claim, expired } => match claim {
+            Some(claim) => {
+                let submitted =
+                    find_submitted(config, claim).ok_or(RpcClientError::GeneralError(format!(
+                        "Claim '{}' is not submitted, or is already settled",
+                        claim
+                    )))?;
+                config.remove_claim(&submitted.claim)?;
+                Ok(Value::String(format!(
+                    "Claim '{}' is forgotten",
+                    submitted.claim
+                )))
+            }
+            None => {
+                let count = config.prune_claims(Timestamp::now().seconds())?;
+                Ok(Value::String(format!(
+                    "{} expired claims are forgotten",
+                    count
+                )))
+            }
+        },
+        Commands::ClaimTypeAdd {
+            name,
+            schema,
+            proof_schema,
+            description,
+            overwrite,
+        } => {
+            let incorrect = |err: anyhow::Error| RpcClientError::IncorrectRequest(err.to_string());
+            let claim_type = ClaimType {
+                description: description.clone(),
+                schema: schema
+                    .as_deref()
+                    .map(read_schema)
+                    .transpose()
+                    .map_err(incorrect)?,
+                proof_schema: proof_schema
+                    .as_deref()
+                    .map(read_schema)
+                    .transpose()
+                    .map_err(incorrect)?,
+            };
+            config.add_claim_type(name, claim_type, *overwrite)?;
+            Ok(Value::String(format!(
+                "Claim type '{}' is registered",
+                name
+            )))
+        }
+        Commands::ClaimTypeList {} => {
+            let types = config.claim_types();
+            if config.structured() {
+                let rows: Vec<Value> = types
+                    .iter()
+                    .map(|(name, claim_type, builtin)| {
+                        json!({
+                            "name": name,
+                            "description": claim_type.description.clone().unwrap_or_default(),
+                            "schema": claim_type.schema.is_some(),
+                            "proof_schema": claim_type.proof_schema.is_some(),
+                            "builtin": builtin,
+                        })
+                    })
+                    .collect();
+                Ok(Value::Array(rows))
+            } else {
+                let mut lines = vec!["Claim types:".to_string()];
+                for (name, claim_type, builtin) in &types {
+                    let notes: Vec<&str> = [
+                        ("built-in", *builtin),
+                        ("claim schema", claim_type.schema.is_some()),
+                        ("proof schema", claim_type.proof_schema.is_some()),
+                    ]
+                    .into_iter()
+                    .filter(|(_, holds)| *holds)
+                    .map(|(what, _)| what)
+                    .collect();
+                    let mut line = format!("  {}", name);
+                    if let Some(description) = &claim_type.description {
+                        line.push_str(&format!(": {}", description));
+                    }
+                    if !notes.is_empty() {
+                        line.push_str(&format!(" ({})", notes.join(", ")));
+                    }
+                    lines.push(line);
+                }
+                Ok(Value::String(lines.join("\n")))
+            }
+        }
+        Commands::ClaimTypeRemove { name } => {
+            config.remove_claim_type(name)?;
+            Ok(Value::String(format!("Claim type '{}' is removed", name)))
+        }
+        Commands::VerifierRun {
+            account,
+            program,
+            interval,
+            program_timeout,
+            since,
+            state,
+            once,
+            network,
+        } => {
+            let incorrect = |err: anyhow::Error| RpcClientError::IncorrectRequest(err.to_string());
+            let interval = parse_duration(interval).map_err(incorrect)?;
+            let program_timeout = parse_duration(program_timeout).map_err(incorrect)?;
+            let since = match since {
+                Some(since) => Some(
+                    since_time(Some(since), None, Timestamp::now().seconds()).map_err(incorrect)?,
+                ),
+                None => None,
+            };
+            let account = config.get_account(Some(account.as_str()))?;
+            let network = config.get_network(network.clone())?;
+            let mut options = VerifierOptions {
+                program: program.clone(),
+                program_timeout,
+                since,
+                state_file: state.clone().unwrap_or(state_file(
+                    &config.name,
+                    config.file.as_deref(),
+                    &account.name,
+                )),
+            };
+            loop {
+                let processed = verify_claims(config, rpc_client, &network, &account, &options);
+                match processed {
+                    _ if *once => return processed,
+                    // The next polls continue from the saved state
+                    Ok(_) => options.since = None,
+                    // The daemon waits for the network to come back
+                    Err(err) if err.exit_code() == EXIT_CODE_CONNECTION_ERROR => {
+                        info!("Failed to poll the submitted claims: {}", err)
+                    }
+                    Err(err) => return Err(err),
+                }
+                std::thread::sleep(std::time::Duration::from_secs(interval.max(1)));
+            }
         }
         Commands::Pay {
             network,
             to,
             amount,
+            signing,
         } => {
             info!("Making payment: to {} amount {}", to, amount);
             let account = config.get_account(None)?;
             let network = config.get_network(network.clone())?;
-            let nonce = rpc_client.get_nonce(network.clone(), &account.credentials.address)?;
-            let pay_message = PayMessage {
-                from: VslAddress::from_str(&account.credentials.address).unwrap(),
-                to: VslAddress::from_str(&(config.lookup_address(&to)?)).unwrap(),
-                amount: to_hex(amount)?,
-                nonce: nonce.to_string(),
+            let address = &account.credentials.address;
+            let to = config.lookup_address(to)?;
+            let sign = |nonce: u64| -> Result<SignedTransaction, RpcClientError> {
+                let pay_message = PayMessage {
+                    from: VslAddress::from_str(address).unwrap(),
+                    to: VslAddress::from_str(&to).unwrap(),
+                    amount: to_hex(amount, VSL_DECIMALS)?,
+                    nonce: nonce.to_string(),
+                };
+                let message_signed = pay_message.into_signed(&account.credentials.signer()?)?;
+                Ok(SignedTransaction::new(
+                    "vsl_pay",
+                    "payment",
+                    serde_json::to_value(message_signed)?,
+                ))
             };
-            let message_signed = pay_message
-                .into_signed(&private_key_to_signer(&account.credentials.private_key))?;
-            let mut params = ObjectParams::new();
-            params.insert("payment", message_signed);
-            let response = rpc_client.make_request(network, "vsl_pay", params)?;
+            if let Some(output) =
+                unsent_output(config, rpc_client, &network, address, signing, &sign)?
+            {
+                return Ok(output);
+            }
+            let (response, _) =
+                send_signed(config, rpc_client, &network, address, &signing.nonce, &sign)?;
             match response {
                 Value::String(ref claim_id) => Ok(response),
                 _ => Err(RpcClientError::GeneralError(format!(
@@ -306,31 +609,190 @@ pub fn execute_command<T: RpcClientInterface>(
                 ))),
             }
         }
-        Commands::AccountCreate { name, overwrite } => {
-            let credentials = config.generate_credentials(None)?;
-            let new_account = config.create_account(name.clone(), credentials, *overwrite)?;
+        Commands::PayBatch {
+            file,
+            report,
+            network,
+        } => transfer_batch(config, rpc_client, network, None, file, report),
+        Commands::AccountCreate {
+            name,
+            overwrite,
+            unencrypted,
+            mnemonic,
+        } => {
+            let (phrase, derivation, mut credentials) = if *mnemonic {
+                let phrase = generate_mnemonic()?;
+                let (private_key, derivation) = derive_private_key(&phrase, 0)?;
+                let credentials = config.generate_credentials(Some(private_key))?;
+                (Some(phrase), Some(derivation), credentials)
+            } else {
+                (None, None, config.generate_credentials(None)?)
+            };
+            if !*unencrypted {
+                credentials.encrypt(&read_new_password(&format!(
+                    "New password for account {}: ",
+                    name
+                ))?)?;
+            }
+            let new_account =
+                config.create_account(name.clone(), credentials, derivation, *overwrite)?;
             config.use_account(&new_account.name);
-            Ok(Value::String(format!(
+            let created = format!(
                 "Account {} is created, address: {}",
                 name, new_account.credentials.address
-            )))
+            );
+            match phrase {
+                Some(phrase) => Ok(Value::String(format!(
+                    "{}\nMnemonic phrase (it is not stored, write it down to recover the account): {}",
+                    created, phrase
+                ))),
+                None => Ok(Value::String(created)),
+            }
         }
         Commands::AccountLoad {
             name,
             private_key,
+            mnemonic,
+            index,
             overwrite,
+            unencrypted,
         } => {
-            let credentials = config.generate_credentials(private_key.clone())?;
-            let new_account = config.create_account(name.clone(), credentials, *overwrite)?;
+            let (derivation, mut credentials) = match mnemonic {
+                Some(phrase) => {
+                    let (private_key, derivation) = derive_private_key(phrase, *index)?;
+                    (
+                        Some(derivation),
+                        config.generate_credentials(Some(private_key))?,
+                    )
+                }
+                None => (None, config.generate_credentials(private_key.clone())?),
+            };
+            if !*unencrypted {
+                credentials.encrypt(&read_new_password(&format!(
+                    "New password for account {}: ",
+                    name
+                ))?)?;
+            }
+            let new_account =
+                config.create_account(name.clone(), credentials, derivation, *overwrite)?;
             config.use_account(&new_account.name);
             Ok(Value::String(format!("Account {} is loaded", name)))
         }
-        Commands::AccountExport { name, file } => {
+        Commands::AccountExternal {
+            name,
+            address,
+            command,
+            overwrite,
+        } => {
+            let command = shlex::split(command).ok_or(RpcClientError::IncorrectRequest(
+                format!("Invalid external signer command: {}", command),
+            ))?;
+            let credentials = config.external_credentials(address, Some(command), None)?;
+            let new_account = config.create_account(name.clone(), credentials, None, *overwrite)?;
+            config.use_account(&new_account.name);
+            Ok(Value::String(format!(
+                "Account {} with an external signer is loaded, address: {}",
+                name, new_account.credentials.address
+            )))
+        }
+        Commands::AccountRemote {
+            name,
+            address,
+            url,
+            overwrite,
+        } => {
+            let credentials = config.external_credentials(address, None, Some(url.clone()))?;
+            let new_account = config.create_account(name.clone(), credentials, None, *overwrite)?;
+            config.use_account(&new_account.name);
+            Ok(Value::String(format!(
+                "Account {} with a remote signer is loaded, address: {}",
+                name, new_account.credentials.address
+            )))
+        }
+        Commands::AccountWatch {
+            name,
+            address,
+            overwrite,
+        } => {
+            let credentials = config.watch_credentials(address)?;
+            let new_account = config.create_account(name.clone(), credentials, None, *overwrite)?;
+            config.use_account(&new_account.name);
+            Ok(Value::String(format!(
+                "Watch-only account {} is added, address: {}",
+                name, new_account.credentials.address
+            )))
+        }
+        Commands::AccountEncrypt { name } => {
+            let names: Vec<String> = match name {
+                Some(name) => vec![config.get_account(Some(name))?.name],
+                None => config
+                    .list_accounts()
+                    .into_iter()
+                    .filter(|(_, account)| account.credentials.kind() == "plain")
+                    .map(|(name, _)| name.clone())
+                    .collect(),
+            };
+            if names.is_empty() {
+                return Ok(Value::String(
+                    "All accounts are already encrypted".to_string(),
+                ));
+            }
+            let password = read_new_password("New password for accounts: ")?;
+            for name in &names {
+                config.encrypt_account(name, &password)?;
+            }
+            Ok(Value::String(format!(
+                "Accounts are encrypted: {}",
+                names.join(", ")
+            )))
+        }
+        Commands::AccountImport {
+            name,
+            keystore,
+            overwrite,
+        } => {
+            let json = std::fs::read_to_string(keystore).map_err(|err| {
+                RpcClientError::GeneralError(format!(
+                    "Failed to read the keystore file '{}': {}",
+                    keystore, err
+                ))
+            })?;
+            let json: Value = serde_json::from_str(&json)?;
+            let password = read_password(&format!("Password for keystore {}: ", keystore))?;
+            let credentials = config.keystore_credentials(json, &password)?;
+            let new_account = config.create_account(name.clone(), credentials, None, *overwrite)?;
+            config.use_account(&new_account.name);
+            Ok(Value::String(format!(
+                "Account {} is imported, address: {}",
+                name, new_account.credentials.address
+            )))
+        }
+        Commands::AccountExport {
+            name,
+            file,
+            keystore,
+        } => {
             let account = config.get_account(if name != "" { Some(&name) } else { None })?;
+            if keystore != "" {
+                let json = account.credentials.export_keystore(|| {
+                    read_new_password(&format!("New password for keystore {}: ", keystore))
+                })?;
+                std::fs::write(keystore, serde_json::to_string_pretty(&json)?).map_err(|err| {
+                    RpcClientError::GeneralError(format!(
+                        "Failed to save the keystore to the file: '{}'",
+                        keystore
+                    ))
+                })?;
+                return Ok(Value::String(format!(
+                    "Account keystore is exported to file {}",
+                    keystore
+                )));
+            }
+            let private_key = account.credentials.private_key()?;
             if file == "" {
-                Ok(Value::String(account.credentials.private_key.clone()))
+                Ok(Value::String(private_key))
             } else {
-                std::fs::write(file, account.credentials.private_key.clone()).map_err(|err| {
+                std::fs::write(file, private_key).map_err(|err| {
                     RpcClientError::GeneralError(format!(
                         "Failed to save the private key to the file: '{}'",
                         file
@@ -355,7 +817,11 @@ pub fn execute_command<T: RpcClientInterface>(
                 params,
             )
         }
-        Commands::AccountBalance { network, account } => {
+        Commands::AccountBalance {
+            network,
+            account,
+            raw,
+        } => {
             let account_id = match account {
                 Some(acc) => config.lookup_address(&acc)?,
                 None => config.get_account(None)?.credentials.address,
@@ -363,11 +829,12 @@ pub fn execute_command<T: RpcClientInterface>(
             info!("Getting balance of account: '{}'", account_id);
             let mut params = ObjectParams::new();
             params.insert("account_id", account_id)?;
-            rpc_client.make_request(
+            let balance = rpc_client.make_request(
                 config.get_network(network.clone())?,
                 "vsl_getBalance",
                 params,
-            )
+            )?;
+            Ok(format_balance(balance, VSL_DECIMALS, *raw))
         }
         Commands::AccountStateGet { network, account } => {
             let account_id = match account {
@@ -387,25 +854,38 @@ pub fn execute_command<T: RpcClientInterface>(
             network,
             account,
             state,
+            encoding,
+            max_size,
+            signing,
         } => {
+            let state = &check_single_stdin(&[state.as_str()], config.mode)
+                .and_then(|_| read_text_input(state, *encoding, *max_size, "state"))
+                .map_err(|err| RpcClientError::IncorrectRequest(err.to_string()))?;
             let account_name = account.as_deref();
             let account = config.get_account(account_name)?;
             let network = config.get_network(network.clone())?;
-            let nonce = rpc_client.get_nonce(network.clone(), &account.credentials.address)?;
-            info!(
-                "Setting state of account: '{}'",
-                account.credentials.address
-            );
-            let message = SetStateMessage {
-                from: VslAddress::from_str(&account.credentials.address).unwrap(),
-                state: state.clone(),
-                nonce: nonce.to_string(),
+            let address = &account.credentials.address;
+            info!("Setting state of account: '{}'", address);
+            let sign = |nonce: u64| -> Result<SignedTransaction, RpcClientError> {
+                let message = SetStateMessage {
+                    from: VslAddress::from_str(address).unwrap(),
+                    state: state.clone(),
+                    nonce: nonce.to_string(),
+                };
+                let message_signed = message.into_signed(&account.credentials.signer()?)?;
+                Ok(SignedTransaction::new(
+                    "vsl_setAccountState",
+                    "state",
+                    serde_json::to_value(message_signed)?,
+                ))
             };
-            let message_signed =
-                message.into_signed(&private_key_to_signer(&account.credentials.private_key))?;
-            let mut params = ObjectParams::new();
-            params.insert("state", message_signed)?;
-            let response = rpc_client.make_request(network, "vsl_setAccountState", params)?;
+            if let Some(output) =
+                unsent_output(config, rpc_client, &network, address, signing, &sign)?
+            {
+                return Ok(output);
+            }
+            let (response, _) =
+                send_signed(config, rpc_client, &network, address, &signing.nonce, &sign)?;
             match response {
                 Value::String(_) => Ok(response),
                 _ => Err(RpcClientError::GeneralError(format!(
@@ -414,6 +894,34 @@ pub fn execute_command<T: RpcClientInterface>(
                 ))),
             }
         }
+        Commands::AccountNonce {
+            account,
+            reset,
+            network,
+        } => {
+            let address = match account {
+                Some(account) => config.lookup_address(account)?,
+                None => config.get_account(None)?.credentials.address,
+            };
+            let network = config.get_network(network.clone())?;
+            if *reset {
+                config.reset_nonce(&network, &address)?;
+            }
+            let (nonce, cached) = match config.cached_nonce(&network, &address)? {
+                Some(nonce) => (nonce, true),
+                None => {
+                    let nonce = rpc_client.get_nonce(network.clone(), &address)?;
+                    config.advance_nonce(&network, &address, nonce)?;
+                    (nonce, false)
+                }
+            };
+            Ok(json!({
+                "address": address,
+                "network": network.name,
+                "nonce": nonce,
+                "cached": cached,
+            }))
+        }
         Commands::AccountUse { network, name } => match config.use_account(name) {
             Ok(_) => {
                 let account = config.get_account(None)?;
@@ -434,10 +942,10 @@ pub fn execute_command<T: RpcClientInterface>(
                 ))
             } else {
                 let account = config.get_account(None)?;
-                if *json {
+                if *json || *table || config.structured() {
                     let value: Value =
                         json!({ "name": account.name, "address": account.credentials.address });
-                    Ok(value)
+                    Ok(table_output(*table, value))
                 } else {
                     Ok(Value::String(format!(
                         "  {}: {}",
@@ -453,18 +961,21 @@ pub fn execute_command<T: RpcClientInterface>(
                 ))
             } else {
                 let networks = config.list_accounts();
-                if *json {
+                if *json || *table || config.structured() {
                     let mut json_map = serde_json::Map::new();
                     for (name, account) in networks {
                         json_map.insert(
                             name.clone(),
                             json!({
                                 "name": account.name,
-                                "address": account.credentials.address
+                                "address": account.credentials.address,
+                                "encrypted": account.credentials.is_encrypted(),
+                                "signer": account.credentials.kind(),
+                                "derivation": account.derivation
                             }),
                         );
                     }
-                    Ok(Value::Object(json_map))
+                    Ok(table_output(*table, Value::Object(json_map)))
                 } else {
                     let mut lines = Vec::new();
                     lines.push(String::from("Available accounts:"));
@@ -472,13 +983,105 @@ pub fn execute_command<T: RpcClientInterface>(
                         lines.push(String::from("   No accounts are present."));
                     } else {
                         for (name, account) in networks {
-                            lines.push(format!("  {}: {}", name, account.credentials.address));
+                            match &account.derivation {
+                                Some(derivation) => lines.push(format!(
+                                    "  {}: {} -- seed {}, index {} ({})",
+                                    name,
+                                    account.credentials.address,
+                                    derivation.seed,
+                                    derivation.index,
+                                    derivation.path
+                                )),
+                                None if account.credentials.is_watch_only() => lines.push(format!(
+                                    "  {}: {} -- watch-only",
+                                    name, account.credentials.address
+                                )),
+                                None => lines
+                                    .push(format!("  {}: {}", name, account.credentials.address)),
+                            }
                         }
                     }
                     Ok(Value::String(lines.join("\n")))
                 }
             }
         }
+        Commands::AccountVerifiers {
+            action,
+            verifier,
+            account,
+        } => {
+            let verifier = match verifier {
+                Some(verifier) => Some(config.lookup_address(verifier)?),
+                None => None,
+            };
+            let account = match (action, verifier) {
+                (VerifiersAction::Add, Some(verifier)) => config
+                    .update_account(account.as_deref(), |account| account.add_verifier(verifier))?,
+                (VerifiersAction::Remove, Some(verifier)) => config
+                    .update_account(account.as_deref(), |account| {
+                        account.remove_verifier(&verifier)
+                    })?,
+                (VerifiersAction::List, _) => config.get_account(account.as_deref())?,
+                (_, None) => {
+                    return Err(RpcClientError::IncorrectRequest(
+                        "The verifier address is not set".to_string(),
+                    ));
+                }
+            };
+            Ok(json!({
+                "account": account.name,
+                "verifiers": account.signatures,
+                "quorum": account.quorum,
+            }))
+        }
+        Commands::AccountQuorum { quorum, account } => {
+            let account =
+                config.update_account(account.as_deref(), |account| account.set_quorum(*quorum))?;
+            Ok(Value::String(format!(
+                "Account {} quorum is {} of {} verifiers",
+                account.name,
+                account.quorum,
+                account.signatures.len()
+            )))
+        }
+        Commands::AccountSign { message, account } => {
+            let account = config.get_account(account.as_deref())?;
+            let message = read_message(config, message)?;
+            let signature = account.credentials.signer()?.sign_message_sync(&message)?;
+            Ok(Value::String(signature.to_string()))
+        }
+        Commands::AccountVerify {
+            address,
+            signature,
+            message,
+        } => {
+            let address = config.lookup_address(address)?;
+            let message = read_message(config, message)?;
+            let signature = Signature::from_str(signature).map_err(|err| {
+                RpcClientError::IncorrectRequest(format!("Invalid signature: {}", err))
+            })?;
+            let signer = signature
+                .recover_address_from_msg(&message)
+                .map_err(|err| {
+                    RpcClientError::IncorrectRequest(format!(
+                        "Failed to recover the signer: {}",
+                        err
+                    ))
+                })?
+                .to_string()
+                .to_ascii_lowercase();
+            if signer == address {
+                Ok(Value::String(format!(
+                    "The signature is valid, signed by {}",
+                    signer
+                )))
+            } else {
+                Err(RpcClientError::GeneralError(format!(
+                    "The signature is invalid: signed by {}, expected {}",
+                    signer, address
+                )))
+            }
+        }
         Commands::AccountRemove { name } => {
             info!("Removing account '{}'", name);
             match config.remove_account(name) {
@@ -493,57 +1096,97 @@ pub fn execute_command<T: RpcClientInterface>(
             network,
             asset,
             account,
+            raw,
         } => {
             let account_id = match account {
                 Some(acc) => config.lookup_address(&acc)?,
                 None => config.get_account(None)?.credentials.address,
             };
             let asset_id = config.lookup_identifier(asset)?;
+            let network = config.get_network(network.clone())?;
             info!("Getting balance of asset: '{}'", asset_id);
             let mut params = ObjectParams::new();
             params.insert("account_id", account_id)?;
-            params.insert("assert_id", asset_id)?;
-            rpc_client.make_request(
-                config.get_network(network.clone())?,
-                "vsl_getAssetBalance",
-                params,
-            )
+            params.insert("assert_id", asset_id.clone())?;
+            let balance =
+                rpc_client.make_request(network.clone(), "vsl_getAssetBalance", params)?;
+            if *raw {
+                return Ok(balance);
+            }
+            let decimals = asset_decimals(rpc_client, network, &asset_id)?;
+            Ok(format_balance(balance, decimals, false))
         }
-        Commands::AssetBalances { network, account } => {
+        Commands::AssetBalances {
+            network,
+            account,
+            raw,
+        } => {
             let account_id = match account {
                 Some(acc) => config.lookup_identifier(&acc)?,
                 None => config.get_account(None)?.credentials.address,
             };
+            let network = config.get_network(network.clone())?;
             info!("Getting balances of all assets of: '{}'", account_id);
             let mut params = ObjectParams::new();
             params.insert("account_id", account_id)?;
-            rpc_client.make_request(
-                config.get_network(network.clone())?,
-                "vsl_getAssetBalances",
-                params,
-            )
+            let balances =
+                rpc_client.make_request(network.clone(), "vsl_getAssetBalances", params)?;
+            match balances {
+                // The balances are keyed by asset ids, each one is formatted with its decimals
+                Value::Object(map) if !*raw => {
+                    let mut formatted = serde_json::Map::new();
+                    for (asset_id, balance) in map {
+                        // The balance of an asset with unknown decimals is kept raw
+                        let balance = match asset_decimals(rpc_client, network.clone(), &asset_id) {
+                            Ok(decimals) => format_balance(balance, decimals, false),
+                            Err(_) => balance,
+                        };
+                        formatted.insert(asset_id, balance);
+                    }
+                    Ok(Value::Object(formatted))
+                }
+                _ => Ok(balances),
+            }
         }
         Commands::AssetCreate {
             network,
             symbol,
             decimals,
             supply,
+            signing,
         } => {
             let account = config.get_account(None)?;
             let network = config.get_network(network.clone())?;
-            let nonce = rpc_client.get_nonce(network.clone(), &account.credentials.address)?;
-            let message = CreateAssetMessage {
-                account_id: VslAddress::from_str(&account.credentials.address).unwrap(),
-                nonce: nonce.to_string(),
-                ticker_symbol: symbol.clone(),
-                decimals: u8::from_str_radix(&decimals, 10).unwrap(),
-                total_supply: to_hex(supply)?,
+            let decimals = u8::from_str_radix(&decimals, 10).map_err(|_| {
+                RpcClientError::IncorrectRequest(format!(
+                    "Invalid number of decimals: {}, must be an integer from 0 to 255",
+                    decimals
+                ))
+            })?;
+            let total_supply = to_hex(supply, decimals)?;
+            let address = &account.credentials.address;
+            let sign = |nonce: u64| -> Result<SignedTransaction, RpcClientError> {
+                let message = CreateAssetMessage {
+                    account_id: VslAddress::from_str(address).unwrap(),
+                    nonce: nonce.to_string(),
+                    ticker_symbol: symbol.clone(),
+                    decimals: decimals,
+                    total_supply: total_supply.clone(),
+                };
+                let message_signed = message.into_signed(&account.credentials.signer()?)?;
+                Ok(SignedTransaction::new(
+                    "vsl_createAsset",
+                    "asset_data",
+                    serde_json::to_value(message_signed)?,
+                ))
             };
-            let message_signed =
-                message.into_signed(&private_key_to_signer(&account.credentials.private_key))?;
-            let mut params = ObjectParams::new();
-            params.insert("asset_data", message_signed);
-            let response = rpc_client.make_request(network, "vsl_createAsset", params)?;
+            if let Some(output) =
+                unsent_output(config, rpc_client, &network, address, signing, &sign)?
+            {
+                return Ok(output);
+            }
+            let (response, _) =
+                send_signed(config, rpc_client, &network, address, &signing.nonce, &sign)?;
             match response {
                 Value::Object(ref map) => match map.get("asset_id") {
                     Some(value) => match value {
@@ -572,22 +1215,48 @@ pub fn execute_command<T: RpcClientInterface>(
             asset,
             to,
             amount,
+            signing,
         } => {
             let account = config.get_account(None)?;
             let network = config.get_network(network.clone())?;
-            let nonce = rpc_client.get_nonce(network.clone(), &account.credentials.address)?;
-            let message = TransferAssetMessage {
-                asset_id: config.lookup_identifier(asset)?,
-                from: VslAddress::from_str(&account.credentials.address).unwrap(),
-                to: VslAddress::from_str(&(config.lookup_address(&to)?)).unwrap(),
-                amount: to_hex(amount)?,
-                nonce: nonce.to_string(),
+            let asset_id = config.lookup_identifier(asset)?;
+            // The decimals are only needed to scale a decimal amount, like `1.5`
+            let decimals = if amount.contains('.') {
+                if signing.sign_only {
+                    return Err(RpcClientError::IncorrectRequest(format!(
+                        "Invalid amount: {}, with `--sign-only` it must be an integer of base units: the decimals of the asset are not known offline",
+                        amount
+                    )));
+                }
+                asset_decimals(rpc_client, network.clone(), &asset_id)?
+            } else {
+                0
             };
-            let message_signed =
-                message.into_signed(&private_key_to_signer(&account.credentials.private_key))?;
-            let mut params = ObjectParams::new();
-            params.insert("transfer_asset", message_signed);
-            let response = rpc_client.make_request(network, "vsl_transferAsset", params)?;
+            let amount = to_hex(amount, decimals)?;
+            let address = &account.credentials.address;
+            let to = config.lookup_address(to)?;
+            let sign = |nonce: u64| -> Result<SignedTransaction, RpcClientError> {
+                let message = TransferAssetMessage {
+                    asset_id: asset_id.clone(),
+                    from: VslAddress::from_str(address).unwrap(),
+                    to: VslAddress::from_str(&to).unwrap(),
+                    amount: amount.clone(),
+                    nonce: nonce.to_string(),
+                };
+                let message_signed = message.into_signed(&account.credentials.signer()?)?;
+                Ok(SignedTransaction::new(
+                    "vsl_transferAsset",
+                    "transfer_asset",
+                    serde_json::to_value(message_signed)?,
+                ))
+            };
+            if let Some(output) =
+                unsent_output(config, rpc_client, &network, address, signing, &sign)?
+            {
+                return Ok(output);
+            }
+            let (response, _) =
+                send_signed(config, rpc_client, &network, address, &signing.nonce, &sign)?;
             match response {
                 Value::String(ref claim_id) => Ok(response),
                 _ => Err(RpcClientError::GeneralError(format!(
@@ -596,6 +1265,12 @@ pub fn execute_command<T: RpcClientInterface>(
                 ))),
             }
         }
+        Commands::AssetTransferBatch {
+            asset,
+            file,
+            report,
+            network,
+        } => transfer_batch(config, rpc_client, network, Some(asset), file, report),
         Commands::AssetGet { network, asset } => {
             let mut params = ObjectParams::new();
             let asset_id = config.lookup_identifier(&asset)?;
@@ -611,6 +1286,121 @@ pub fn execute_command<T: RpcClientInterface>(
             "vsl_getHealth",
             ObjectParams::new(),
         ),
+        Commands::RpcCall {
+            method,
+            params,
+            params_json,
+            sign_as,
+            sign_param,
+            network,
+        } => {
+            let mut call_params = rpc_call_params(params, params_json)?;
+            if let (Some(account), Some(sign_param)) = (sign_as, sign_param) {
+                let account = config.get_account(Some(account.as_str()))?;
+                let message =
+                    call_params
+                        .remove(sign_param)
+                        .ok_or(RpcClientError::IncorrectRequest(format!(
+                            "The parameter '{}' to sign is absent",
+                            sign_param
+                        )))?;
+                let message_signed = message.into_signed(&account.credentials.signer()?)?;
+                call_params.insert(sign_param.clone(), serde_json::to_value(message_signed)?);
+            }
+            if config.dry_run {
+                return Ok(dry_run_output(method, Value::Object(call_params)));
+            }
+            info!("Calling '{}' with parameters: {:?}", method, call_params);
+            let mut object_params = ObjectParams::new();
+            for (key, value) in call_params {
+                object_params.insert(&key, value)?;
+            }
+            rpc_client.make_request(config.get_network(network.clone())?, method, object_params)
+        }
+        Commands::TxSend { file, network } => {
+            let transaction = SignedTransaction::read(file)?;
+            info!(
+                "Sending signed message from '{}' with '{}'",
+                file, transaction.method
+            );
+            if config.dry_run {
+                return Ok(dry_run_output(
+                    &transaction.method,
+                    json!({ transaction.param: transaction.signed }),
+                ));
+            }
+            let (address, nonce) =
+                transaction
+                    .sender_nonce()
+                    .ok_or(RpcClientError::IncorrectRequest(format!(
+                        "Signed message in '{}' has no sender address, or nonce",
+                        file
+                    )))?;
+            let submitted = transaction.submitted_claim();
+            let network = config.get_network(network.clone())?;
+            // The message is signed already: it is sent with its own nonce, which advances the cache
+            let sign =
+                |_: u64| -> Result<SignedTransaction, RpcClientError> { Ok(transaction.clone()) };
+            let (response, _) =
+                send_signed(config, rpc_client, &network, &address, &Some(nonce), &sign)?;
+            // The claim, submitted offline, is recorded as `claim:submit` does
+            if let (Some(submitted), Value::String(claim_id)) = (submitted, &response) {
+                if !config.has_claim(&submitted.claim) {
+                    config.add_claim(submitted, claim_id.clone())?;
+                }
+            }
+            Ok(response)
+        }
+        Commands::TxDecode { input } => {
+            let json = if input.trim_start().starts_with('{') {
+                input.clone()
+            } else {
+                std::fs::read_to_string(input).map_err(|err| {
+                    RpcClientError::IncorrectRequest(format!(
+                        "Failed to read signed message from '{}': {}",
+                        input, err
+                    ))
+                })?
+            };
+            let value: Value = serde_json::from_str(&json).map_err(|err| {
+                RpcClientError::IncorrectRequest(format!("Signed message is not a JSON: {}", err))
+            })?;
+            decode(&value).map_err(|err| RpcClientError::IncorrectRequest(err.to_string()))
+        }
+        Commands::AddressAdd { name, address } => {
+            let address = config.make_valid_address(address)?;
+            let previous = config.name_address(name, address.clone())?;
+            Ok(Value::String(name_added_message(name, &address, previous)))
+        }
+        Commands::AddressList { json, table } => {
+            list_hex_map(config, config.address_book(), "addresses", *json, *table)
+        }
+        Commands::AddressRemove { name, address } => {
+            config.remove_address(name, address.as_deref())?;
+            Ok(Value::String(name_removed_message(name, address)))
+        }
+        Commands::AddressShow { name, json } => {
+            show_hex_map(config.address_book(), name, *json || config.structured())
+        }
+        Commands::IdAdd { name, id } => {
+            let id = config.make_valid_identifier(id)?;
+            let previous = config.name_identifier(name, id.clone())?;
+            Ok(Value::String(name_added_message(name, &id, previous)))
+        }
+        Commands::IdList { json, table } => list_hex_map(
+            config,
+            config.identifier_book(),
+            "identifiers",
+            *json,
+            *table,
+        ),
+        Commands::IdRemove { name, id } => {
+            config.remove_identifier(name, id.as_deref())?;
+            Ok(Value::String(name_removed_message(name, id)))
+        }
+        Commands::IdShow { name, json } => {
+            show_hex_map(config.identifier_book(), name, *json || config.structured())
+        }
         Commands::NetworkAdd { name, url, port } => match config.add_network(name, url, port) {
             Ok(network) => {
                 if check_network_is_up(rpc_client, network.clone()) {
@@ -633,7 +1423,7 @@ pub fn execute_command<T: RpcClientInterface>(
                 ))
             } else {
                 let networks = config.list_networks();
-                if *json {
+                if *json || *table || config.structured() {
                     let mut json_map = serde_json::Map::new();
                     for (name, network) in networks {
                         json_map.insert(
@@ -644,7 +1434,7 @@ pub fn execute_command<T: RpcClientInterface>(
                             }),
                         );
                     }
-                    Ok(Value::Object(json_map))
+                    Ok(table_output(*table, Value::Object(json_map)))
                 } else {
                     let mut lines = Vec::new();
                     lines.push(String::from("Available networks:"));
@@ -707,9 +1497,9 @@ pub fn execute_command<T: RpcClientInterface>(
                         } else {
                             "down"
                         };
-                        if *json {
+                        if *json || *table || config.structured() {
                             let value: Value = json!({ "name": network.name, "url": network.url, "port": network.port, "status": status });
-                            Ok(value)
+                            Ok(table_output(*table, value))
                         } else {
                             Ok(Value::String(format!(
                                 "  {}: {}:{} -- {}",
@@ -794,6 +1584,8 @@ pub fn execute_command<T: RpcClientInterface>(
             } else {
                 info!("starting vsl-core (server)...");
                 let (new_server, opt_tempdir) = init_local_server(&db, init, *force)?;
+                // The state of the new server is fresh, the cached nonces are stale
+                config.reset_network_nonces(&local_network)?;
                 config.set_server(Some(new_server));
                 if let Some(tempdir) = opt_tempdir {
                     Ok(Value::String(format!(
@@ -881,7 +1673,7 @@ pub fn execute_command<T: RpcClientInterface>(
                 ))
             } else {
                 let configs = Configs::read()?;
-                if *json {
+                if *json || *table || config.structured() {
                     let mut json_map = serde_json::Map::new();
                     for (name, path) in configs.configs {
                         json_map.insert(
@@ -892,7 +1684,7 @@ pub fn execute_command<T: RpcClientInterface>(
                             }),
                         );
                     }
-                    Ok(Value::Object(json_map))
+                    Ok(table_output(*table, Value::Object(json_map)))
                 } else {
                     let mut lines = Vec::new();
                     lines.push(String::from("Available configurations:"));
@@ -914,31 +1706,716 @@ pub fn execute_command<T: RpcClientInterface>(
     }
 }
 
-/// Converts the argument, which may be hexadecimal or decimal to a hexadecimal representation
-fn to_hex(s: &str) -> Result<String, RpcClientError> {
-    if s.starts_with("0x") {
-        // Detect the hex input
-        if u64::from_str_radix(s.strip_prefix("0x").unwrap_or(s), 16).is_ok() {
-            // Return as is
-            Ok(s.to_string())
-        } else {
-            Err(RpcClientError::GeneralError(format!(
-                "Invalid number format: {}, must be a hexadecimal or decimal integer",
-                s
-            )))
+/// Replaces the timestamps in a node response with human-readable dates, unless `raw` is set.
+fn human_times(mut value: Value, raw: bool) -> Value {
+    if !raw {
+        humanize_timestamps(&mut value);
+    }
+    value
+}
+
+/// Finds the submitted claim by its text, its id, or a name of the id. A name may refer
+/// to several ids: the ids of other claims, or of the claim settlement.
+fn find_submitted(config: &mut Config, claim: &str) -> Option<SubmittedClaim> {
+    config.get_claim(claim).ok().cloned().or_else(|| {
+        let claim_ids = match config.identifier_book().ids(claim) {
+            Some(ids) => ids.clone(),
+            None => config.lookup_identifier(claim).into_iter().collect(),
+        };
+        claim_ids
+            .iter()
+            .find_map(|claim_id| config.submitted.get(claim_id).cloned())
+    })
+}
+
+/// The row of a submitted claim in the claim inventory
+fn submitted_row(id: Option<String>, submitted: &SubmittedClaim, now: u64) -> Value {
+    let expires = submitted.expires.seconds();
+    json!({
+        "claim": submitted.claim,
+        "id": id,
+        "type": submitted.claim_type,
+        "nonce": submitted.nonce,
+        "fee": submitted.fee,
+        "expires": expires,
+        "status": ClaimState::of(Some(expires), false, now),
+    })
+}
+
+/// The state of a claim, given by its text, its id, or a name of the id. The claim is settled,
+/// if the network has the settled claim. Otherwise it is submitted or expired by the local record
+/// of the submitted claim, or unknown, if there is no record. The settlement of a submitted claim
+/// is looked up among the claims, settled `since` the time.
+fn claim_status<T: RpcClientInterface>(
+    config: &mut Config,
+    rpc_client: &mut T,
+    network: &Network,
+    claim: &str,
+    since: u64,
+) -> Result<(ClaimState, Value), RpcClientError> {
+    let submitted = find_submitted(config, claim);
+    // The ids to query: the id of the submitted claim, otherwise the ids of the name,
+    // i.e. the ids of a claim and of its settlement
+    let claim_ids = match &submitted {
+        Some(submitted) => vec![
+            VerifiedClaim::claim_id_hash(
+                &submitted.from.address,
+                &submitted.nonce,
+                &submitted.claim,
+            )
+            .to_string(),
+        ],
+        None => match config.identifier_book().ids(claim) {
+            Some(ids) if !ids.is_empty() => ids.clone(),
+            _ => match config.lookup_identifier(claim) {
+                Ok(id) => vec![id],
+                // Neither a known name, nor an id
+                Err(_) => {
+                    let state = ClaimState::Unknown;
+                    return Ok((
+                        state,
+                        json!({ "claim": claim, "id": null, "status": state }),
+                    ));
+                }
+            },
+        },
+    };
+    let mut claim_id = claim_ids[0].clone();
+    let mut settled = None;
+    for id in claim_ids {
+        let mut params = ObjectParams::new();
+        params.insert("claim_id", &id)?;
+        match rpc_client.make_request(network.clone(), "vsl_getSettledClaimById", params) {
+            Ok(Value::Null) => {}
+            Ok(found) => {
+                claim_id = id;
+                settled = Some(found);
+                break;
+            }
+            // The node rejects the ids of the claims, which are not settled
+            Err(err) if err.exit_code() == EXIT_CODE_REJECTED => {}
+            Err(err) => return Err(err),
         }
+    }
+    if let (None, Some(submitted)) = (&settled, &submitted) {
+        // The settled claims of the sender since the previous check
+        let mut params = ObjectParams::new();
+        params.insert("address", submitted.from.address.to_string())?;
+        params.insert("since", Timestamp::from_seconds(since))?;
+        let claims =
+            rpc_client.make_request(network.clone(), "vsl_listSettledClaimsForReceiver", params)?;
+        settled = match claims {
+            Value::Array(claims) => claims
+                .into_iter()
+                .find(|settled| contains_string(settled, &claim_id)),
+            _ => None,
+        };
+    }
+    let expires = submitted
+        .as_ref()
+        .map(|submitted| submitted.expires.seconds());
+    let state = ClaimState::of(expires, settled.is_some(), Timestamp::now().seconds());
+    let mut status = json!({
+        "claim": submitted.as_ref().map(|submitted| submitted.claim.clone()),
+        "id": claim_id,
+        "status": state,
+        "expires": expires,
+    });
+    if let Some(settled) = settled {
+        status["settled"] = settled;
+    }
+    Ok((state, status))
+}
+
+/// Renders the data of a command as a table, if `--table` is given.
+fn table_output(table: bool, value: Value) -> Value {
+    if table {
+        Value::String(render(&value, OutputFormat::Table))
     } else {
-        // Decimal input, parsing as decimal
-        if let Ok(num) = s.parse::<u64>() {
-            // Convert to hexadecimal
-            Ok(format!("0x{:x}", num))
+        value
+    }
+}
+
+fn name_added_message(name: &str, id: &str, previous: Option<String>) -> String {
+    match previous {
+        Some(previous) => format!("'{}' is renamed from '{}' to '{}'", id, previous, name),
+        None => format!("'{}' is added as '{}'", id, name),
+    }
+}
+
+fn name_removed_message(name: &str, id: &Option<String>) -> String {
+    match id {
+        Some(id) => format!("'{}' is removed from '{}'", id, name),
+        None => format!("'{}' is removed", name),
+    }
+}
+
+/// Lists the names of a book (addresses or identifiers) with their hex IDs.
+fn list_hex_map(
+    config: &Config,
+    book: &HexMap,
+    what: &str,
+    json: bool,
+    table: bool,
+) -> Result<Value, RpcClientError> {
+    if json && table {
+        return Err(RpcClientError::GeneralError(
+            "--table= cannot also be provided when using --json=".to_string(),
+        ));
+    }
+    let names = book.list();
+    if json || table || config.structured() {
+        let mut json_map = serde_json::Map::new();
+        for (name, ids) in names {
+            json_map.insert(name.clone(), json!(ids));
+        }
+        Ok(table_output(table, Value::Object(json_map)))
+    } else {
+        let mut lines = Vec::new();
+        lines.push(format!("Known {}:", what));
+        if names.is_empty() {
+            lines.push(format!("   No {} are present.", what));
         } else {
-            Err(RpcClientError::GeneralError(format!(
-                "Invalid number format: {}, must be a hexadecimal or decimal integer",
-                s
+            let width = names.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+            for (name, ids) in names {
+                for (i, id) in ids.iter().enumerate() {
+                    // A name with several IDs is printed once, on the first line
+                    let name = if i == 0 { name.as_str() } else { "" };
+                    lines.push(format!("  {:width$}  {}", name, id, width = width));
+                }
+            }
+        }
+        Ok(Value::String(lines.join("\n")))
+    }
+}
+
+/// Shows the hex IDs of a name, or the name of a hex ID.
+fn show_hex_map(book: &HexMap, name: &str, json: bool) -> Result<Value, RpcClientError> {
+    let (name, ids) = match (book.ids(name), book.lookup_name(name)) {
+        (Some(ids), _) => (name.to_string(), ids.clone()),
+        (None, Some(known)) => (known.clone(), vec![name.to_ascii_lowercase()]),
+        (None, None) => {
+            return Err(RpcClientError::GeneralError(format!(
+                "'{}' is neither a known name, nor a known ID",
+                name
+            )));
+        }
+    };
+    if json {
+        Ok(json!({ "name": name, "ids": ids }))
+    } else if ids.len() == 1 {
+        Ok(Value::String(format!("{}: {}", name, ids[0])))
+    } else {
+        let mut lines = vec![format!("{} has {} IDs:", name, ids.len())];
+        lines.extend(ids.iter().map(|id| format!("  {}", id)));
+        Ok(Value::String(lines.join("\n")))
+    }
+}
+
+/// Reads the message argument: a text itself, a file content, if the argument is `@<path>`,
+/// or the standard input, if it is `-`
+fn read_message(config: &Config, message: &str) -> Result<Vec<u8>, RpcClientError> {
+    check_single_stdin(&[message], config.mode)
+        .and_then(|_| read_input(message, "message", DEFAULT_MAX_INPUT_SIZE))
+        .map_err(|err| RpcClientError::IncorrectRequest(err.to_string()))
+}
+
+/// Converts the amount argument to a hexadecimal representation. The argument may be a hexadecimal
+/// or decimal integer of base units, or a decimal number like `1.5`, scaled by `decimals`.
+fn to_hex(s: &str, decimals: u8) -> Result<String, RpcClientError> {
+    parse_amount(s, decimals)
+        .map(amount_to_hex)
+        .map_err(|err| RpcClientError::IncorrectRequest(err.to_string()))
+}
+
+/// Formats the balance returned by a node as a decimal number of whole tokens.
+/// The `raw` balance, or a balance of an unexpected format, is returned as is.
+fn format_balance(balance: Value, decimals: u8, raw: bool) -> Value {
+    match amount_from_value(&balance) {
+        Some(amount) if !raw => Value::String(format_amount(amount, decimals)),
+        _ => balance,
+    }
+}
+
+/// The nonce of a message, which is not sent: the `--nonce` value, if it is set, otherwise
+/// the cached next nonce of the address, or the current nonce, requested from the network.
+fn message_nonce<T: RpcClientInterface>(
+    config: &Config,
+    rpc_client: &mut T,
+    network: &Network,
+    address: &str,
+    nonce: &Option<u64>,
+) -> Result<u64, RpcClientError> {
+    if let Some(nonce) = nonce {
+        return Ok(*nonce);
+    }
+    if let Some(nonce) = config.cached_nonce(network, address)? {
+        return Ok(nonce);
+    }
+    match rpc_client.get_nonce(network.clone(), address) {
+        // The nonce is not essential to show a request in the dry run
+        Err(err) if config.dry_run => {
+            info!("Failed to get the nonce, 0 is used in the dry run: {}", err);
+            Ok(0)
+        }
+        result => result,
+    }
+}
+
+/// The result of a command, which does not send its signed message: the message with `--sign-only`,
+/// or the request in the dry run. Returns `None`, if the message must be sent.
+fn unsent_output<T: RpcClientInterface>(
+    config: &Config,
+    rpc_client: &mut T,
+    network: &Network,
+    address: &str,
+    signing: &SignOnlyArgs,
+    sign: &impl Fn(u64) -> Result<SignedTransaction, RpcClientError>,
+) -> Result<Option<Value>, RpcClientError> {
+    if !signing.sign_only && !config.dry_run {
+        return Ok(None);
+    }
+    let nonce = message_nonce(config, rpc_client, network, address, &signing.nonce)?;
+    let transaction = sign(nonce)?;
+    if signing.sign_only {
+        return sign_only_output(transaction, &signing.out).map(Some);
+    }
+    Ok(Some(dry_run_output(
+        &transaction.method,
+        json!({ transaction.param: transaction.signed }),
+    )))
+}
+
+/// Signs a message of the `address` with the `nonce`, or with the nonce, reserved in the cache,
+/// and sends it. The next nonce is cached after a successful send, and the cached nonce is dropped
+/// after a failure. If the node rejects a reserved nonce, the message is signed again with the nonce,
+/// requested from the network, and sent once more. Returns the response and the nonce of the message.
+fn send_signed<T: RpcClientInterface>(
+    config: &mut Config,
+    rpc_client: &mut T,
+    network: &Network,
+    address: &str,
+    nonce: &Option<u64>,
+    sign: &impl Fn(u64) -> Result<SignedTransaction, RpcClientError>,
+) -> Result<(Value, u64), RpcClientError> {
+    let mut resynced = false;
+    loop {
+        let message_nonce = match nonce {
+            Some(nonce) => *nonce,
+            None => config.reserve_nonce(network, address, || {
+                rpc_client.get_nonce(network.clone(), address)
+            })?,
+        };
+        let transaction = sign(message_nonce)?;
+        let mut params = ObjectParams::new();
+        params.insert(&transaction.param, transaction.signed)?;
+        let result = rpc_client.make_request(network.clone(), &transaction.method, params);
+        // The cache is only an optimization: failing to update it does not fail the command
+        let cached = match &result {
+            Ok(_) => config.advance_nonce(network, address, message_nonce + 1),
+            Err(_) => config.reset_nonce(network, address).map(|_| ()),
+        };
+        if let Err(err) = cached {
+            info!("Failed to cache the nonce of '{}': {}", address, err);
+        }
+        match result {
+            Err(err) if is_nonce_error(&err) && nonce.is_none() && !resynced => {
+                info!(
+                    "Nonce {} of '{}' is rejected, retrying with the nonce of the network: {}",
+                    message_nonce, address, err
+                );
+                resynced = true;
+            }
+            result => return result.map(|response| (response, message_nonce)),
+        }
+    }
+}
+
+/// Transfers VSL tokens, or the `asset`, to the recipients of the batch `file`, row by row,
+/// with sequential nonces. The result of every row is written to the `report` file, and the rows,
+/// which were transferred in a previous run, are skipped.
+fn transfer_batch<T: RpcClientInterface>(
+    config: &mut Config,
+    rpc_client: &mut T,
+    network: &Option<String>,
+    asset: Option<&String>,
+    file: &str,
+    report: &Option<String>,
+) -> Result<Value, RpcClientError> {
+    let rows =
+        read_rows(file).map_err(|err| RpcClientError::IncorrectRequest(format!("{:#}", err)))?;
+    let report = report.clone().unwrap_or(report_file(file));
+    let previous = read_report(&report)?;
+    let account = config.get_account(None)?;
+    let network = config.get_network(network.clone())?;
+    let asset_id = match asset {
+        Some(asset) => Some(config.lookup_identifier(asset)?),
+        None => None,
+    };
+    // The decimals of an asset are only needed to scale decimal amounts, like `1.5`
+    let decimals = match &asset_id {
+        None => VSL_DECIMALS,
+        Some(asset_id) if rows.iter().any(|row| row.amount.contains('.')) => {
+            asset_decimals(rpc_client, network.clone(), asset_id)?
+        }
+        Some(_) => 0,
+    };
+    let mut results = Vec::new();
+    let mut requests = Vec::new();
+    let mut next_nonce = None;
+    let done = find_done(&previous, &rows);
+    for (i, row) in rows.iter().enumerate() {
+        let number = i + 1;
+        if let Some(done) = done[i] {
+            results.push(RowResult {
+                row: number,
+                status: RowStatus::Skipped,
+                ..done.clone()
+            });
+            continue;
+        }
+        info!(
+            "Transferring row {}: to {} amount {}",
+            number, row.recipient, row.amount
+        );
+        let mut transfer = || -> Result<Value, RpcClientError> {
+            let address = &account.credentials.address;
+            let to = config.lookup_address(&row.recipient)?;
+            let amount = to_hex(&row.amount, decimals)?;
+            let sign = |nonce: u64| -> Result<SignedTransaction, RpcClientError> {
+                let from = VslAddress::from_str(address).unwrap();
+                let to = VslAddress::from_str(&to).unwrap();
+                match &asset_id {
+                    Some(asset_id) => {
+                        let message = TransferAssetMessage {
+                            asset_id: asset_id.clone(),
+                            from,
+                            to,
+                            amount: amount.clone(),
+                            nonce: nonce.to_string(),
+                        };
+                        let signed = message.into_signed(&account.credentials.signer()?)?;
+                        Ok(SignedTransaction::new(
+                            "vsl_transferAsset",
+                            "transfer_asset",
+                            serde_json::to_value(signed)?,
+                        ))
+                    }
+                    None => {
+                        let message = PayMessage {
+                            from,
+                            to,
+                            amount: amount.clone(),
+                            nonce: nonce.to_string(),
+                        };
+                        let signed = message.into_signed(&account.credentials.signer()?)?;
+                        Ok(SignedTransaction::new(
+                            "vsl_pay",
+                            "payment",
+                            serde_json::to_value(signed)?,
+                        ))
+                    }
+                }
+            };
+            if config.dry_run {
+                // The nonces are not cached in the dry run, so the rows take sequential nonces
+                let nonce = message_nonce(config, rpc_client, &network, address, &next_nonce)?;
+                next_nonce = Some(nonce + 1);
+                let transaction = sign(nonce)?;
+                return Ok(dry_run_output(
+                    &transaction.method,
+                    json!({ transaction.param: transaction.signed }),
+                ));
+            }
+            send_signed(config, rpc_client, &network, address, &None, &sign)
+                .map(|(response, _)| response)
+        };
+        let result = transfer();
+        let (status, result, error) = match result {
+            Ok(response) if config.dry_run => {
+                requests.push(response);
+                (RowStatus::Ok, None, None)
+            }
+            Ok(Value::String(claim_id)) => (RowStatus::Ok, Some(claim_id), None),
+            Ok(response) => (RowStatus::Ok, Some(response.to_string()), None),
+            Err(err) => (RowStatus::Failed, None, Some(err.to_string())),
+        };
+        results.push(RowResult {
+            row: number,
+            recipient: row.recipient.clone(),
+            amount: row.amount.clone(),
+            status,
+            result,
+            error,
+        });
+        if !config.dry_run {
+            write_report(&report, &results)?;
+        }
+    }
+    if config.dry_run {
+        return Ok(Value::Array(requests));
+    }
+    let failed: Vec<&RowResult> = results
+        .iter()
+        .filter(|result| result.status == RowStatus::Failed)
+        .collect();
+    if failed.is_empty() {
+        Ok(serde_json::to_value(results)?)
+    } else {
+        Err(RpcClientError::GeneralError(format!(
+            "{} of {} rows failed, the report is written to '{}'. Run the command again to retry the failed rows:\n{}",
+            failed.len(),
+            results.len(),
+            report,
+            failed
+                .iter()
+                .map(|result| format!(
+                    "row {}: {}",
+                    result.row,
+                    result.error.clone().unwrap_or_default()
+                ))
+                .collect::<Vec<String>>()
+                .join("\n")
+        )))
+    }
+}
+
+/// The signed message, which settles the claim of the `target_claim_id` by the `address`
+fn settle_message(
+    account: &Account,
+    address: &str,
+    nonce: u64,
+    target_claim_id: String,
+) -> Result<SignedTransaction, RpcClientError> {
+    let message = SettleClaimMessage {
+        from: VslAddress::from_str(address).unwrap(),
+        nonce: nonce.to_string(),
+        target_claim_id,
+    };
+    let message_signed = message.into_signed(&account.credentials.signer()?)?;
+    Ok(SignedTransaction::new(
+        "vsl_settleClaim",
+        "settled_claim",
+        serde_json::to_value(message_signed)?,
+    ))
+}
+
+/// Polls the claims, submitted to the verifier `account`, checks the new ones with the checker
+/// program, and settles the accepted ones. A claim is accepted in the state file before it is
+/// settled, so that after a restart it is not checked again, and it is settled only if the network
+/// does not have it settled yet. Returns the processed claims. In the dry run the checker is not run:
+/// returns the new claims, which would be checked, and the settlement requests of the accepted ones.
+fn verify_claims<T: RpcClientInterface>(
+    config: &mut Config,
+    rpc_client: &mut T,
+    network: &Network,
+    account: &Account,
+    options: &VerifierOptions,
+) -> Result<Value, RpcClientError> {
+    let state_file = options.state_file.as_str();
+    let address = account.credentials.address.clone();
+    let now = Timestamp::now().seconds();
+    let mut state = VerifierState::read(
+        state_file,
+        &address,
+        options.since.unwrap_or(now.saturating_sub(3600)),
+    )?;
+    if let Some(since) = options.since {
+        state.since = since;
+    }
+    info!(
+        "Polling claims submitted to verifier '{}' since {}",
+        address, state.since
+    );
+    let mut params = ObjectParams::new();
+    params.insert("address", &address)?;
+    params.insert("since", Timestamp::from_seconds(state.since))?;
+    let list = rpc_client.make_request(
+        network.clone(),
+        "vsl_listSubmittedClaimsForReceiver",
+        params,
+    )?;
+    // The claims, accepted in a previous run, are settled first: they may be out of the poll window
+    let mut to_settle = state.accepted();
+    let mut processed = Vec::new();
+    // The claims, which the checker has failed to check before, are checked again first
+    let mut unchecked = state.errored();
+    unchecked.extend(
+        submitted_claims(&list)
+            .into_iter()
+            .filter(|(id, _)| !state.claims.contains_key(id)),
+    );
+    let mut to_check = Vec::new();
+    for (id, submitted) in unchecked {
+        if submitted.expires.seconds() <= now {
+            info!("Claim '{}' has expired, it is skipped", id);
+            state.claims.remove(&id);
+            continue;
+        }
+        if config.dry_run {
+            // The checker may have side effects, so it is not run in the dry run
+            info!("Claim '{}' would be checked by '{}'", id, options.program);
+            to_check.push(json!(id));
+            continue;
+        }
+        let record = state.check(&id, &submitted, options, now)?;
+        let reason = record.reason.clone().unwrap_or_default();
+        match record.verdict {
+            Verdict::Accepted => {
+                info!("Claim '{}' is accepted", id);
+                to_settle.push(id.clone());
+            }
+            Verdict::Rejected => {
+                info!("Claim '{}' is rejected: {}", id, reason);
+                processed.push(json!({ "id": id, "verdict": Verdict::Rejected, "reason": reason }));
+            }
+            _ => info!(
+                "Failed to check claim '{}', it is checked again on the next poll: {}",
+                id, reason
+            ),
+        }
+        state.write(state_file)?;
+    }
+    let mut requests = Vec::new();
+    let mut next_nonce = None;
+    for id in to_settle {
+        // The settlement may have been sent before a restart
+        let mut params = ObjectParams::new();
+        params.insert("claim_id", &id)?;
+        if let Ok(settled) =
+            rpc_client.make_request(network.clone(), "vsl_getSettledClaimById", params)
+        {
+            if !settled.is_null() {
+                info!("Claim '{}' is already settled", id);
+                state
+                    .claims
+                    .insert(id, ClaimRecord::new(Verdict::Settled, now));
+                if !config.dry_run {
+                    state.write(state_file)?;
+                }
+                continue;
+            }
+        }
+        let sign = |nonce: u64| settle_message(account, &address, nonce, id.clone());
+        if config.dry_run {
+            // The nonces are not cached in the dry run, so the settlements take sequential nonces
+            let nonce = message_nonce(config, rpc_client, network, &address, &next_nonce)?;
+            next_nonce = Some(nonce + 1);
+            let transaction = sign(nonce)?;
+            requests.push(dry_run_output(
+                &transaction.method,
+                json!({ transaction.param: transaction.signed }),
+            ));
+            continue;
+        }
+        let mut record = ClaimRecord::new(Verdict::Settled, now);
+        match send_signed(config, rpc_client, network, &address, &None, &sign) {
+            Ok((response, _)) => {
+                info!("Claim '{}' is settled: {}", id, response);
+                record.settled_id = response.as_str().map(str::to_string);
+            }
+            // The settlement is retried on the next poll
+            Err(err) if err.exit_code() == EXIT_CODE_CONNECTION_ERROR || is_nonce_error(&err) => {
+                info!("Failed to settle claim '{}': {}", id, err);
+                continue;
+            }
+            Err(err) => {
+                info!("Settlement of claim '{}' is rejected: {}", id, err);
+                record.verdict = Verdict::Failed;
+                record.reason = Some(err.to_string());
+            }
+        }
+        processed.push(json!({
+            "id": id,
+            "verdict": record.verdict,
+            "settled_id": record.settled_id,
+            "reason": record.reason,
+        }));
+        state.claims.insert(id, record);
+        state.write(state_file)?;
+    }
+    if config.dry_run {
+        return Ok(json!({ "to_check": to_check, "requests": requests }));
+    }
+    state.since = state.since.max(now.saturating_sub(POLL_OVERLAP));
+    state.write(state_file)?;
+    Ok(Value::Array(processed))
+}
+
+/// The result of a command in the dry run: the request, which would be sent.
+fn dry_run_output(method: &str, params: Value) -> Value {
+    json!({ "method": method, "params": params })
+}
+
+/// The result of a command with `--sign-only`: the signed message is not sent, but returned,
+/// or written to the `out` file.
+fn sign_only_output(
+    transaction: SignedTransaction,
+    out: &Option<String>,
+) -> Result<Value, RpcClientError> {
+    match out {
+        Some(file) => {
+            transaction.write(file)?;
+            Ok(Value::String(format!(
+                "Signed message for '{}' is written to '{}'",
+                transaction.method, file
             )))
         }
+        None => Ok(serde_json::to_value(transaction)?),
+    }
+}
+
+/// Collects the parameters of `rpc:call`: the `--params-json` object, extended with
+/// the `--param key=value` pairs. A value is parsed as JSON, if possible, otherwise it is a string.
+fn rpc_call_params(
+    params: &Vec<String>,
+    params_json: &Option<String>,
+) -> Result<serde_json::Map<String, Value>, RpcClientError> {
+    let mut call_params = match params_json {
+        Some(json) => match serde_json::from_str(json) {
+            Ok(Value::Object(map)) => map,
+            _ => {
+                return Err(RpcClientError::IncorrectRequest(format!(
+                    "--params-json must be a JSON object, got: '{}'",
+                    json
+                )));
+            }
+        },
+        None => serde_json::Map::new(),
+    };
+    for param in params {
+        let (key, value) = param
+            .split_once('=')
+            .ok_or(RpcClientError::IncorrectRequest(format!(
+                "--param must be in the form `key=value`, got: '{}'",
+                param
+            )))?;
+        let value = serde_json::from_str(value).unwrap_or(Value::String(value.to_string()));
+        call_params.insert(key.to_string(), value);
     }
+    Ok(call_params)
+}
+
+/// Requests the number of decimals of an asset from a node
+fn asset_decimals(
+    rpc_client: &mut impl RpcClientInterface,
+    network: Network,
+    asset_id: &str,
+) -> Result<u8, RpcClientError> {
+    let mut params = ObjectParams::new();
+    params.insert("asset_id", asset_id)?;
+    let asset = rpc_client.make_request(network, "vsl_getAssetById", params)?;
+    let decimals = match asset.get("decimals") {
+        Some(Value::Number(decimals)) => decimals.as_u64(),
+        Some(Value::String(decimals)) => decimals.parse().ok(),
+        _ => None,
+    };
+    decimals
+        .and_then(|decimals| u8::try_from(decimals).ok())
+        .ok_or(RpcClientError::IncorrectResponse(format!(
+            "Asset {} must have the number of decimals, got: '{}'",
+            asset_id, asset
+        )))
 }
 
 #[cfg(test)]
@@ -946,23 +2423,35 @@ mod tests {
     use super::to_hex;
     #[test]
     fn test_to_hex() {
-        assert!(to_hex("0x100").expect("must be correct input to 'to_hex'") == "0x100");
-        assert!(to_hex("100").expect("must be correct input to 'to_hex'") == "0x64");
+        assert!(to_hex("0x100", 18).expect("must be correct input to 'to_hex'") == "0x100");
+        assert!(to_hex("100", 18).expect("must be correct input to 'to_hex'") == "0x64");
         assert!(
-            to_hex("1234567890123456").expect("must be correct input to 'to_hex'")
+            to_hex("1234567890123456", 18).expect("must be correct input to 'to_hex'")
                 == "0x462d53c8abac0"
         );
         assert!(
-            to_hex("12345678901234567").expect("must be correct input to 'to_hex'")
+            to_hex("12345678901234567", 18).expect("must be correct input to 'to_hex'")
                 == "0x2bdc545d6b4b87"
         );
         assert!(
-            to_hex("0x462d53c8abac0").expect("must be correct input to 'to_hex'")
+            to_hex("0x462d53c8abac0", 18).expect("must be correct input to 'to_hex'")
                 == "0x462d53c8abac0"
         );
         assert!(
-            to_hex("0x2bdc545d6b4b87").expect("must be correct input to 'to_hex'")
+            to_hex("0x2bdc545d6b4b87", 18).expect("must be correct input to 'to_hex'")
                 == "0x2bdc545d6b4b87"
         );
+        // Above u64
+        assert!(
+            to_hex("1000000000000000000000000", 18).expect("must be correct input to 'to_hex'")
+                == "0xd3c21bcecceda1000000"
+        );
+        // Scaled by decimals
+        assert!(to_hex("1.5", 2).expect("must be correct input to 'to_hex'") == "0x96");
+        assert!(
+            to_hex("1000000.0", 18).expect("must be correct input to 'to_hex'")
+                == "0xd3c21bcecceda1000000"
+        );
+        assert!(to_hex("18446744073709551616x", 18).is_err());
     }
 }
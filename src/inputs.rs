@@ -0,0 +1,165 @@
+#![allow(unused)]
+
+use crate::configs::CliMode;
+use anyhow::Result;
+use anyhow::anyhow;
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use clap::ValueEnum;
+use std::fs;
+use std::io::Read as _;
+
+/// The default maximal size of an input in bytes, after encoding: 1 MiB
+pub const DEFAULT_MAX_INPUT_SIZE: usize = 1 << 20;
+
+/// The encoding of the bytes of an input into the text of a message
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Encoding {
+    /// The input as is, it must be a valid UTF-8 text
+    #[default]
+    Raw,
+    /// Base64 with padding
+    Base64,
+    /// Hexadecimal, `0x`-prefixed
+    Hex,
+}
+
+/// Reads the bytes of an argument, which is:
+/// - `@<path>`: the content of the file
+/// - `-`: the standard input
+/// - `@@<text>`: the text, starting with `@`
+/// - any other text as is
+///
+/// A file, or the standard input, larger than `max_size` bytes is rejected before it is read whole.
+pub fn read_input(arg: &str, what: &str, max_size: usize) -> Result<Vec<u8>> {
+    if arg == "-" {
+        let mut bytes = Vec::new();
+        std::io::stdin()
+            .take(max_size as u64 + 1)
+            .read_to_end(&mut bytes)
+            .map_err(|err| anyhow!("Failed to read the {} from stdin: {}", what, err))?;
+        if bytes.len() > max_size {
+            return Err(anyhow!(
+                "The {} from stdin is too large: more than {} bytes",
+                what,
+                max_size
+            ));
+        }
+        return Ok(bytes);
+    }
+    if let Some(text) = arg.strip_prefix("@@") {
+        return Ok(format!("@{}", text).into_bytes());
+    }
+    match arg.strip_prefix('@') {
+        Some(path) => {
+            let size = fs::metadata(path)
+                .map_err(|err| anyhow!("Failed to read the {} file '{}': {}", what, path, err))?
+                .len();
+            if size > max_size as u64 {
+                return Err(anyhow!(
+                    "The {} file '{}' is too large: {} bytes, at most {} bytes are allowed",
+                    what,
+                    path,
+                    size,
+                    max_size
+                ));
+            }
+            fs::read(path)
+                .map_err(|err| anyhow!("Failed to read the {} file '{}': {}", what, path, err))
+        }
+        None => Ok(arg.as_bytes().to_vec()),
+    }
+}
+
+/// Encodes the bytes of an input into a text.
+pub fn encode(bytes: Vec<u8>, encoding: Encoding, what: &str) -> Result<String> {
+    match encoding {
+        Encoding::Raw => String::from_utf8(bytes).map_err(|_| {
+            anyhow!(
+                "The {} is not a valid UTF-8 text, use `base64` or `hex` encoding for binary data",
+                what
+            )
+        }),
+        Encoding::Base64 => Ok(BASE64.encode(bytes)),
+        Encoding::Hex => Ok(format!("0x{}", hex::encode(bytes))),
+    }
+}
+
+/// Reads an argument (see `read_input`), encodes it, and checks that the result is not
+/// larger than `max_size` bytes.
+pub fn read_text_input(
+    arg: &str,
+    encoding: Encoding,
+    max_size: usize,
+    what: &str,
+) -> Result<String> {
+    // The encoding only grows the input, so a larger input is rejected before it is encoded
+    let text = encode(read_input(arg, what, max_size)?, encoding, what)?;
+    if text.len() > max_size {
+        return Err(anyhow!(
+            "The {} is too large: {} bytes, at most {} bytes are allowed",
+            what,
+            text.len(),
+            max_size
+        ));
+    }
+    Ok(text)
+}
+
+/// Checks that at most one of the arguments is read from the standard input, and none is read
+/// in REPL mode, where the standard input holds the commands.
+pub fn check_single_stdin(args: &[&str], mode: CliMode) -> Result<()> {
+    let count = args.iter().filter(|arg| **arg == "-").count();
+    if count > 0 && matches!(mode, CliMode::MultiCommand) {
+        return Err(anyhow!(
+            "The standard input (`-`) can't be read in REPL mode, use `@<path>` instead"
+        ));
+    }
+    if count > 1 {
+        return Err(anyhow!("Only one argument may be read from stdin (`-`)"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_input() {
+        assert_eq!(read_input("text", "claim", 10).unwrap(), b"text");
+        assert_eq!(read_input("@@text", "claim", 10).unwrap(), b"@text");
+        assert!(read_input("@/nonexistent/file", "claim", 10).is_err());
+        let file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(file.path(), [0u8, 1, 2]).unwrap();
+        let arg = format!("@{}", file.path().display());
+        assert_eq!(read_input(&arg, "proof", 3).unwrap(), vec![0u8, 1, 2]);
+        assert!(read_input(&arg, "proof", 2).is_err());
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(
+            encode(b"hi".to_vec(), Encoding::Raw, "claim").unwrap(),
+            "hi"
+        );
+        assert!(encode(vec![0xff, 0xfe], Encoding::Raw, "claim").is_err());
+        assert_eq!(
+            encode(b"hi".to_vec(), Encoding::Base64, "claim").unwrap(),
+            "aGk="
+        );
+        assert_eq!(
+            encode(b"hi".to_vec(), Encoding::Hex, "claim").unwrap(),
+            "0x6869"
+        );
+        assert!(read_text_input("hello", Encoding::Raw, 4, "claim").is_err());
+        assert_eq!(
+            read_text_input("hello", Encoding::Raw, 5, "claim").unwrap(),
+            "hello"
+        );
+        assert!(check_single_stdin(&["-", "x"], CliMode::SingleCommand).is_ok());
+        assert!(check_single_stdin(&["-", "-"], CliMode::SingleCommand).is_err());
+        assert!(check_single_stdin(&["-", "x"], CliMode::MultiCommand).is_err());
+        assert!(check_single_stdin(&["x"], CliMode::MultiCommand).is_ok());
+    }
+}
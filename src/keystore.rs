@@ -0,0 +1,107 @@
+#![allow(unused)]
+
+use alloy::hex::FromHex as _;
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::Result;
+use anyhow::anyhow;
+use rand::thread_rng;
+use serde_json::Value;
+use tempfile::TempDir;
+
+/// The environment variable with a password for the encrypted accounts.
+pub const VSL_CLI_PASSWORD: &str = "VSL_CLI_PASSWORD";
+/// The environment variable with a path to a file, containing a password for the encrypted accounts.
+pub const VSL_CLI_PASSWORD_FILE: &str = "VSL_CLI_PASSWORD_FILE";
+
+/// The name of a keystore file inside of a temporary directory.
+const KEYSTORE_FILE_NAME: &str = "keystore.json";
+
+/// Reads a password from the `VSL_CLI_PASSWORD` environment variable, or from the file
+/// pointed by `VSL_CLI_PASSWORD_FILE`. Returns `None` if none of them is set.
+fn password_from_env() -> Result<Option<String>> {
+    if let Ok(password) = std::env::var(VSL_CLI_PASSWORD) {
+        return Ok(Some(password));
+    }
+    match std::env::var(VSL_CLI_PASSWORD_FILE) {
+        Ok(path) => {
+            let password = std::fs::read_to_string(&path)
+                .map_err(|err| anyhow!("Failed to read the password file '{}': {}", path, err))?;
+            Ok(Some(password.trim_end_matches(['\r', '\n']).to_string()))
+        }
+        Err(_) => Ok(None),
+    }
+}
+
+/// Reads a password of an existing keystore: from the environment, or prompts it in a terminal.
+pub fn read_password(prompt: &str) -> Result<String> {
+    match password_from_env()? {
+        Some(password) => Ok(password),
+        None => rpassword::prompt_password(prompt)
+            .map_err(|err| anyhow!("Failed to read the password: {}", err)),
+    }
+}
+
+/// Reads a password for a new keystore. When prompted in a terminal, the password is asked twice.
+pub fn read_new_password(prompt: &str) -> Result<String> {
+    if let Some(password) = password_from_env()? {
+        return Ok(password);
+    }
+    let password = rpassword::prompt_password(prompt)
+        .map_err(|err| anyhow!("Failed to read the password: {}", err))?;
+    let confirmed = rpassword::prompt_password("Repeat the password: ")
+        .map_err(|err| anyhow!("Failed to read the password: {}", err))?;
+    if password != confirmed {
+        return Err(anyhow!("The passwords do not match"));
+    }
+    Ok(password)
+}
+
+/// Encrypts a hex private key with a password into a Web3 Secret Storage (V3 keystore) JSON.
+pub fn encrypt_private_key(private_key: &str, password: &str) -> Result<Value> {
+    let private_key = private_key.strip_prefix("0x").unwrap_or(private_key);
+    let bytes = <[u8; 32]>::from_hex(private_key).or(Err(anyhow!(
+        "The private key must be a 32 bytes hex string"
+    )))?;
+    let dir = TempDir::new()?;
+    PrivateKeySigner::encrypt_keystore(
+        dir.path(),
+        &mut thread_rng(),
+        bytes,
+        password,
+        Some(KEYSTORE_FILE_NAME),
+    )
+    .map_err(|err| anyhow!("Failed to encrypt the private key: {}", err))?;
+    let keystore = std::fs::read_to_string(dir.path().join(KEYSTORE_FILE_NAME))?;
+    Ok(serde_json::from_str(&keystore)?)
+}
+
+/// Decrypts a Web3 Secret Storage (V3 keystore) JSON with a password.
+pub fn decrypt_keystore(keystore: &Value, password: &str) -> Result<PrivateKeySigner> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join(KEYSTORE_FILE_NAME);
+    std::fs::write(&path, serde_json::to_string(keystore)?)?;
+    PrivateKeySigner::decrypt_keystore(&path, password)
+        .map_err(|err| anyhow!("Failed to decrypt the keystore: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIVATE_KEY: &str = "b6dd863bea551b5bb27ce9917316a01ea4c331f24e0e4fe56e28eb430f175ed7";
+
+    #[test]
+    fn test_keystore_roundtrip() {
+        let keystore = encrypt_private_key(PRIVATE_KEY, "secret").expect("must encrypt");
+        assert!(keystore.get("crypto").is_some());
+        assert!(!keystore.to_string().contains(PRIVATE_KEY));
+        let signer = decrypt_keystore(&keystore, "secret").expect("must decrypt");
+        assert_eq!(hex::encode(signer.to_bytes()), PRIVATE_KEY);
+    }
+
+    #[test]
+    fn test_keystore_wrong_password() {
+        let keystore = encrypt_private_key(PRIVATE_KEY, "secret").expect("must encrypt");
+        assert!(decrypt_keystore(&keystore, "not a secret").is_err());
+    }
+}
@@ -1,11 +1,24 @@
 #![allow(unused)]
 
 pub mod accounts;
+pub mod amounts;
+pub mod batch;
+pub mod claims;
 pub mod commands;
 pub mod configs;
 pub mod execute;
+pub mod inputs;
+pub mod keystore;
 pub mod networks;
+pub mod nonces;
+pub mod output;
+pub mod query;
 pub mod repl;
 pub mod rpc_client;
 pub mod rpc_server;
+pub mod schema;
+pub mod signers;
+pub mod times;
+pub mod transactions;
 pub mod utils;
+pub mod verifier;
@@ -0,0 +1,281 @@
+use crate::networks::Network;
+use crate::rpc_client::RpcClientError;
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::ErrorKind;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+
+/// How long a command waits for the lock of the nonce cache
+const LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A lock, which is older than this, is left by a killed command and is removed. The lock is only
+/// held while the cache file is read and written: the network is never requested under the lock.
+const LOCK_STALE: Duration = Duration::from_secs(60);
+
+/// The little DB of the next nonces of accounts, so that a nonce is not requested
+/// from the network before every signed message.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Nonces {
+    /// The next nonce by the network name and the account address.
+    known: HashMap<String, u64>,
+}
+
+impl Nonces {
+    /// The key of an account in a network
+    fn key(network: &Network, address: &str) -> String {
+        format!("{}/{}", network.name, address.to_lowercase())
+    }
+
+    /// Returns the cached next nonce of the `address` in the `network`.
+    pub fn get(&self, network: &Network, address: &str) -> Option<u64> {
+        self.known.get(&Self::key(network, address)).copied()
+    }
+
+    /// Caches the next nonce of the `address` in the `network`.
+    pub fn set(&mut self, network: &Network, address: &str, nonce: u64) {
+        self.known.insert(Self::key(network, address), nonce);
+    }
+
+    /// Drops the cached nonce of the `address` in the `network`. Returns the dropped nonce.
+    pub fn reset(&mut self, network: &Network, address: &str) -> Option<u64> {
+        self.known.remove(&Self::key(network, address))
+    }
+
+    /// Drops the cached nonces of all accounts in the `network`, i.e. when its state is reset.
+    pub fn reset_network(&mut self, network: &Network) {
+        let prefix = format!("{}/", network.name);
+        self.known.retain(|key, _| !key.starts_with(&prefix));
+    }
+
+    /// Reads the nonces from a file. There are no nonces, if the file does not exist.
+    pub fn read(file: &Path) -> Result<Nonces> {
+        match fs::read_to_string(file) {
+            Ok(content) => serde_json::from_str(&content)
+                .with_context(|| format!("Invalid nonce cache in '{}'", file.display())),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Nonces::default()),
+            Err(err) => Err(anyhow!(
+                "Failed to read the nonce cache from '{}': {}",
+                file.display(),
+                err
+            )),
+        }
+    }
+
+    /// Writes the nonces to a temporary file and renames it over the `file`, so that
+    /// a concurrent reader never sees a partially written file.
+    fn write(&self, file: &Path) -> Result<()> {
+        let tmp = file.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write the nonce cache to '{}'", tmp.display()))?;
+        fs::rename(&tmp, file)
+            .with_context(|| format!("Failed to write the nonce cache to '{}'", file.display()))
+    }
+
+    /// Changes the nonces in the `file` under an exclusive lock, so that concurrent commands
+    /// neither reserve the same nonce, nor lose the changes of each other.
+    pub fn update<R, E: From<anyhow::Error>>(
+        file: &Path,
+        change: impl FnOnce(&mut Nonces) -> Result<R, E>,
+    ) -> Result<R, E> {
+        let _lock = FileLock::acquire(&file.with_extension("json.lock"))?;
+        let mut nonces = Nonces::read(file)?;
+        let result = change(&mut nonces)?;
+        nonces.write(file)?;
+        Ok(result)
+    }
+}
+
+/// The nonce cache file of a config: `main.json` has its nonces in `main.nonces.json`
+pub fn nonces_file(config_file: &Path) -> PathBuf {
+    config_file.with_extension("nonces.json")
+}
+
+/// A lock file, which exists while the lock is held
+struct FileLock {
+    path: PathBuf,
+    /// Written into the lock file, so that a lock, which is removed as stale and taken by
+    /// another command, is not removed on drop
+    owner: String,
+}
+
+impl FileLock {
+    fn acquire(path: &Path) -> Result<FileLock> {
+        let started = Instant::now();
+        let owner = format!(
+            "{}-{}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|time| time.as_nanos())
+                .unwrap_or_default()
+        );
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(path) {
+                Ok(mut file) => {
+                    let _ = file.write_all(owner.as_bytes());
+                    return Ok(FileLock {
+                        path: path.to_path_buf(),
+                        owner,
+                    });
+                }
+                Err(err) if err.kind() == ErrorKind::AlreadyExists => {
+                    let age = fs::metadata(path)
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                        .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+                    if age.is_some_and(|age| age > LOCK_STALE) {
+                        let _ = fs::remove_file(path);
+                    } else if started.elapsed() > LOCK_TIMEOUT {
+                        return Err(anyhow!(
+                            "Timed out waiting for the lock '{}', remove it, if no other command is running",
+                            path.display()
+                        ));
+                    } else {
+                        std::thread::sleep(Duration::from_millis(20));
+                    }
+                }
+                Err(err) => {
+                    return Err(anyhow!(
+                        "Failed to create the lock '{}': {}",
+                        path.display(),
+                        err
+                    ));
+                }
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        if fs::read_to_string(&self.path).is_ok_and(|owner| owner == self.owner) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Checks if the node has rejected a request because of its nonce: the cached nonce
+/// is out of sync with the network and must be requested again.
+pub fn is_nonce_error(err: &RpcClientError) -> bool {
+    match err {
+        RpcClientError::ConnectionError(_) | RpcClientError::IncorrectResponse(_) => {
+            err.to_string().to_lowercase().contains("nonce")
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nonces() {
+        let network = Network::default();
+        let mut nonces = Nonces::default();
+        assert_eq!(nonces.get(&network, "0xAB"), None);
+        nonces.set(&network, "0xAB", 5);
+        assert_eq!(nonces.get(&network, "0xab"), Some(5));
+        let other = Network {
+            name: "other".to_string(),
+            ..Network::default()
+        };
+        assert_eq!(nonces.get(&other, "0xab"), None);
+        assert_eq!(nonces.reset(&network, "0xab"), Some(5));
+        assert_eq!(nonces.get(&network, "0xab"), None);
+        nonces.set(&network, "0xab", 1);
+        nonces.set(&other, "0xab", 2);
+        nonces.reset_network(&network);
+        assert_eq!(nonces.get(&network, "0xab"), None);
+        assert_eq!(nonces.get(&other, "0xab"), Some(2));
+    }
+
+    #[test]
+    fn test_update() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = nonces_file(&dir.path().join("main.json"));
+        assert!(file.ends_with("main.nonces.json"));
+        let network = Network::default();
+        // Concurrent reservations take distinct nonces
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let file = file.clone();
+                let network = network.clone();
+                std::thread::spawn(move || {
+                    Nonces::update(&file, |nonces| -> Result<u64> {
+                        let nonce = nonces.get(&network, "0xab").unwrap_or(0);
+                        nonces.set(&network, "0xab", nonce + 1);
+                        Ok(nonce)
+                    })
+                    .unwrap()
+                })
+            })
+            .collect();
+        let mut reserved: Vec<u64> = threads
+            .into_iter()
+            .map(|thread| thread.join().unwrap())
+            .collect();
+        reserved.sort();
+        assert_eq!(reserved, (0..8).collect::<Vec<u64>>());
+        assert_eq!(Nonces::read(&file).unwrap().get(&network, "0xab"), Some(8));
+        assert!(!file.with_extension("json.lock").exists());
+    }
+
+    #[test]
+    fn test_lock_owner() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("main.nonces.json.lock");
+        let lock = FileLock::acquire(&path).unwrap();
+        // The lock is removed as stale and taken by another command
+        fs::write(&path, "other").unwrap();
+        drop(lock);
+        assert!(path.exists());
+        fs::remove_file(&path).unwrap();
+        drop(FileLock::acquire(&path).unwrap());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_reserve_nonce() {
+        use crate::configs::CliMode;
+        use crate::configs::Config;
+        let dir = tempfile::TempDir::new().unwrap();
+        let config_file = dir.path().join("main.json");
+        let lock = nonces_file(&config_file).with_extension("json.lock");
+        let mut config = Config::new("main".to_string(), Some(config_file), CliMode::MultiCommand);
+        let network = Network::default();
+        // The network is requested without the lock
+        let fetch = || -> Result<u64> {
+            assert!(!lock.exists());
+            Ok(5)
+        };
+        assert_eq!(config.reserve_nonce(&network, "0xab", fetch).unwrap(), 5);
+        let cached = || -> Result<u64> { panic!("the cached nonce is not fetched") };
+        assert_eq!(config.reserve_nonce(&network, "0xab", cached).unwrap(), 6);
+        assert_eq!(config.cached_nonce(&network, "0xab").unwrap(), Some(7));
+    }
+
+    #[test]
+    fn test_is_nonce_error() {
+        assert!(is_nonce_error(&RpcClientError::IncorrectResponse(
+            "invalid nonce: expected 3, got 2".to_string()
+        )));
+        assert!(!is_nonce_error(&RpcClientError::IncorrectResponse(
+            "insufficient balance".to_string()
+        )));
+        assert!(!is_nonce_error(&RpcClientError::GeneralError(
+            "nonce".to_string()
+        )));
+    }
+}
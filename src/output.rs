@@ -0,0 +1,238 @@
+#![allow(unused)]
+
+use clap::ValueEnum;
+use serde_json::Value;
+
+/// The format of the output of a command result
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Messages as is, data as pretty-printed JSON
+    #[default]
+    Text,
+    /// Pretty-printed JSON
+    Json,
+    /// Compact JSON, one line per element of an array
+    Jsonl,
+    /// Aligned table
+    Table,
+    /// YAML
+    Yaml,
+    /// Comma separated values, with a header line
+    Csv,
+}
+
+impl OutputFormat {
+    /// Is the output intended for data, not for messages: the commands, which return
+    /// a message or a data, should return a data.
+    pub fn is_structured(&self) -> bool {
+        *self != OutputFormat::Text
+    }
+}
+
+/// Renders the result of a command in the `format`.
+pub fn render(value: &Value, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Text => match value {
+            Value::String(str) => str.clone(),
+            _ => render_json(value),
+        },
+        OutputFormat::Json => render_json(value),
+        OutputFormat::Jsonl => match value {
+            Value::Array(values) => values
+                .iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<String>>()
+                .join("\n"),
+            _ => value.to_string(),
+        },
+        OutputFormat::Table => render_table(value),
+        OutputFormat::Yaml => match serde_yaml::to_string(value) {
+            Ok(yaml) => yaml.trim_end().to_string(),
+            Err(err) => format!("Invalid YAML: {}, the response: {}", err, value),
+        },
+        OutputFormat::Csv => render_csv(value),
+    }
+}
+
+fn render_json(value: &Value) -> String {
+    match serde_json::to_string_pretty(value) {
+        Ok(pretty_json) => pretty_json,
+        Err(err) => format!("Invalid JSON: {}, the response: {}", err, value),
+    }
+}
+
+/// The column of a table with the keys of an object of objects, i.e. `{"name": {...}}`
+const KEY_COLUMN: &str = "name";
+
+/// Converts a value to the rows of a table, the first row is a header.
+///
+/// - an array of objects: the columns are the union of the keys of the objects
+/// - an object of objects: the same, the keys of the outer object make the first column
+/// - an object of scalars: two columns, a key and a value
+/// - an array of scalars: a single column
+/// - a scalar: a single cell
+fn to_rows(value: &Value) -> Vec<Vec<String>> {
+    fn is_object(value: &Value) -> bool {
+        matches!(value, Value::Object(_))
+    }
+    let records: Vec<(Option<&String>, &serde_json::Map<String, Value>)> = match value {
+        Value::Array(values) if !values.is_empty() && values.iter().all(is_object) => values
+            .iter()
+            .filter_map(|value| value.as_object().map(|object| (None, object)))
+            .collect(),
+        Value::Object(map) if !map.is_empty() && map.values().all(is_object) => map
+            .iter()
+            .filter_map(|(key, value)| value.as_object().map(|object| (Some(key), object)))
+            .collect(),
+        Value::Object(map) => {
+            let mut rows = vec![vec!["key".to_string(), "value".to_string()]];
+            rows.extend(
+                map.iter()
+                    .map(|(key, value)| vec![key.clone(), cell(value)]),
+            );
+            return rows;
+        }
+        Value::Array(values) => {
+            let mut rows = vec![vec!["value".to_string()]];
+            rows.extend(values.iter().map(|value| vec![cell(value)]));
+            return rows;
+        }
+        _ => return vec![vec![cell(value)]],
+    };
+    let mut columns: Vec<String> = Vec::new();
+    let keyed = records.iter().any(|(key, _)| key.is_some());
+    if keyed {
+        columns.push(KEY_COLUMN.to_string());
+    }
+    for (_, object) in &records {
+        for column in object.keys() {
+            if !columns.contains(column) {
+                columns.push(column.clone());
+            }
+        }
+    }
+    let mut rows = vec![columns.clone()];
+    for (key, object) in records {
+        let row = columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| match (i, key) {
+                // The outer key is shown, unless the object has its own `name`
+                (0, Some(key)) if keyed => {
+                    object.get(column).map(cell).unwrap_or_else(|| key.clone())
+                }
+                _ => object.get(column).map(cell).unwrap_or_default(),
+            })
+            .collect();
+        rows.push(row);
+    }
+    rows
+}
+
+/// The text of a table cell: strings are not quoted, nested values are compact JSON
+fn cell(value: &Value) -> String {
+    match value {
+        Value::String(str) => str.clone(),
+        Value::Null => String::new(),
+        _ => value.to_string(),
+    }
+}
+
+fn render_table(value: &Value) -> String {
+    if let Value::String(str) = value {
+        return str.clone();
+    }
+    let rows = to_rows(value);
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|i| {
+            rows.iter()
+                .filter_map(|row| row.get(i))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let line = |row: &Vec<String>| {
+        row.iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<String>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    let mut lines = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        lines.push(line(row));
+        if i == 0 && rows.len() > 1 {
+            lines.push(
+                widths
+                    .iter()
+                    .map(|width| "-".repeat(*width))
+                    .collect::<Vec<String>>()
+                    .join("  "),
+            );
+        }
+    }
+    lines.join("\n")
+}
+
+fn render_csv(value: &Value) -> String {
+    let escape = |cell: &String| {
+        if cell.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", cell.replace('"', "\"\""))
+        } else {
+            cell.clone()
+        }
+    };
+    to_rows(value)
+        .iter()
+        .map(|row| row.iter().map(escape).collect::<Vec<String>>().join(","))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_table() {
+        let value = json!({
+            "alice": { "address": "0x01", "balance": "10" },
+            "bob": { "address": "0x02" },
+        });
+        assert_eq!(
+            render(&value, OutputFormat::Table),
+            "name   address  balance\n-----  -------  -------\nalice  0x01     10\nbob    0x02"
+        );
+        assert_eq!(
+            render(&json!({ "quorum": 2 }), OutputFormat::Table),
+            "key     value\n------  -----\nquorum  2"
+        );
+    }
+
+    #[test]
+    fn test_render_csv() {
+        let value = json!([
+            { "claim": "a, b", "id": "0x01" },
+            { "claim": "say \"hi\"", "id": "0x02" },
+        ]);
+        assert_eq!(
+            render(&value, OutputFormat::Csv),
+            "claim,id\n\"a, b\",0x01\n\"say \"\"hi\"\"\",0x02"
+        );
+    }
+
+    #[test]
+    fn test_render_jsonl() {
+        let value = json!([{ "a": 1 }, { "b": [1, 2] }]);
+        assert_eq!(
+            render(&value, OutputFormat::Jsonl),
+            "{\"a\":1}\n{\"b\":[1,2]}"
+        );
+        assert_eq!(render(&json!("done"), OutputFormat::Text), "done");
+    }
+}
@@ -0,0 +1,488 @@
+#![allow(unused)]
+
+use anyhow::Result;
+use anyhow::anyhow;
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// A filter of a JSON value: a subset of the `jq` language.
+///
+/// Supported are paths `.a.b`, `."a-b"`, `.["a"]`, `.[0]`, `.[-1]`, `.[]` (with `?` to suppress
+/// errors), pipes `|`, `keys`, `length` and `select(<path> <op> <literal>)`, where `<op>`
+/// is one of `==`, `!=`, `<`, `<=`, `>`, `>=`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    /// The value itself: `.`
+    Identity,
+    /// The path inside of the value
+    Path(Vec<Segment>),
+    /// The keys of an object, or the indexes of an array
+    Keys,
+    /// The length of an array, object or string
+    Length,
+    /// The value itself, if the condition holds, otherwise nothing
+    Select(Box<Filter>, Comparison, Value),
+    /// The output of each filter is the input of the next one
+    Pipe(Vec<Filter>),
+}
+
+/// A segment of a path, the flag is `true`, if the errors are suppressed with `?`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Key(String, bool),
+    Index(i64, bool),
+    Iterate(bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Evaluates the `query` on the `value`. A single result is returned as is,
+/// several (or none) results are collected into an array.
+pub fn query(value: &Value, query: &str) -> Result<Value> {
+    let filter = parse(query)?;
+    let mut results = filter.eval(value)?;
+    if results.len() == 1 {
+        Ok(results.remove(0))
+    } else {
+        Ok(Value::Array(results))
+    }
+}
+
+/// Parses the text of a query.
+pub fn parse(query: &str) -> Result<Filter> {
+    let mut parser = Parser {
+        chars: query.chars().collect(),
+        pos: 0,
+    };
+    let filter = parser.pipe()?;
+    parser.skip_spaces();
+    if parser.pos < parser.chars.len() {
+        return Err(parser.error("unexpected input"));
+    }
+    Ok(filter)
+}
+
+impl Filter {
+    /// Applies the filter to the `value`, the results are a stream of values.
+    pub fn eval(&self, value: &Value) -> Result<Vec<Value>> {
+        match self {
+            Filter::Identity => Ok(vec![value.clone()]),
+            Filter::Path(segments) => {
+                let mut values = vec![value.clone()];
+                for segment in segments {
+                    let mut next = Vec::new();
+                    for value in values {
+                        next.extend(segment.eval(value)?);
+                    }
+                    values = next;
+                }
+                Ok(values)
+            }
+            Filter::Keys => match value {
+                Value::Object(map) => {
+                    let mut keys: Vec<&String> = map.keys().collect();
+                    keys.sort();
+                    Ok(vec![Value::Array(
+                        keys.into_iter()
+                            .map(|key| Value::String(key.clone()))
+                            .collect(),
+                    )])
+                }
+                Value::Array(values) => Ok(vec![Value::Array(
+                    (0..values.len()).map(Value::from).collect(),
+                )]),
+                _ => Err(anyhow!("{} has no keys", type_name(value))),
+            },
+            Filter::Length => match value {
+                Value::Object(map) => Ok(vec![Value::from(map.len())]),
+                Value::Array(values) => Ok(vec![Value::from(values.len())]),
+                Value::String(str) => Ok(vec![Value::from(str.chars().count())]),
+                Value::Null => Ok(vec![Value::from(0)]),
+                _ => Err(anyhow!("{} has no length", type_name(value))),
+            },
+            Filter::Select(filter, comparison, literal) => {
+                for lhs in filter.eval(value)? {
+                    if comparison.holds(&lhs, literal)? {
+                        return Ok(vec![value.clone()]);
+                    }
+                }
+                Ok(Vec::new())
+            }
+            Filter::Pipe(filters) => {
+                let mut values = vec![value.clone()];
+                for filter in filters {
+                    let mut next = Vec::new();
+                    for value in values {
+                        next.extend(filter.eval(&value)?);
+                    }
+                    values = next;
+                }
+                Ok(values)
+            }
+        }
+    }
+}
+
+impl Segment {
+    fn eval(&self, value: Value) -> Result<Vec<Value>> {
+        let result = match (self, value) {
+            (Segment::Key(key, _), Value::Object(mut map)) => {
+                Ok(vec![map.remove(key).unwrap_or(Value::Null)])
+            }
+            (Segment::Key(_, _), Value::Null) => Ok(vec![Value::Null]),
+            (Segment::Key(key, _), value) => Err(anyhow!(
+                "cannot get the key '{}' of {}",
+                key,
+                type_name(&value)
+            )),
+            (Segment::Index(index, _), Value::Array(mut values)) => {
+                let len = values.len() as i64;
+                let index = if *index < 0 { len + index } else { *index };
+                if 0 <= index && index < len {
+                    Ok(vec![values.swap_remove(index as usize)])
+                } else {
+                    Ok(vec![Value::Null])
+                }
+            }
+            (Segment::Index(_, _), Value::Null) => Ok(vec![Value::Null]),
+            (Segment::Index(index, _), value) => Err(anyhow!(
+                "cannot get the index {} of {}",
+                index,
+                type_name(&value)
+            )),
+            (Segment::Iterate(_), Value::Array(values)) => Ok(values),
+            (Segment::Iterate(_), Value::Object(map)) => {
+                Ok(map.into_iter().map(|(_, v)| v).collect())
+            }
+            (Segment::Iterate(_), value) => {
+                Err(anyhow!("cannot iterate over {}", type_name(&value)))
+            }
+        };
+        match (result, self.optional()) {
+            (Err(_), true) => Ok(Vec::new()),
+            (result, _) => result,
+        }
+    }
+
+    fn optional(&self) -> bool {
+        match self {
+            Segment::Key(_, optional)
+            | Segment::Index(_, optional)
+            | Segment::Iterate(optional) => *optional,
+        }
+    }
+}
+
+impl Comparison {
+    fn holds(&self, lhs: &Value, rhs: &Value) -> Result<bool> {
+        let ordering = || -> Result<Ordering> {
+            match (lhs, rhs) {
+                (Value::Number(lhs), Value::Number(rhs)) => lhs
+                    .as_f64()
+                    .zip(rhs.as_f64())
+                    .and_then(|(lhs, rhs)| lhs.partial_cmp(&rhs))
+                    .ok_or(anyhow!("cannot compare {} and {}", lhs, rhs)),
+                (Value::String(lhs), Value::String(rhs)) => Ok(lhs.cmp(rhs)),
+                _ => Err(anyhow!(
+                    "cannot compare {} and {}",
+                    type_name(lhs),
+                    type_name(rhs)
+                )),
+            }
+        };
+        Ok(match self {
+            Comparison::Eq => lhs == rhs,
+            Comparison::Ne => lhs != rhs,
+            Comparison::Lt => ordering()? == Ordering::Less,
+            Comparison::Le => ordering()? != Ordering::Greater,
+            Comparison::Gt => ordering()? == Ordering::Greater,
+            Comparison::Ge => ordering()? != Ordering::Less,
+        })
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+/// The recursive descent parser of queries
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn error(&self, message: &str) -> anyhow::Error {
+        anyhow!("Invalid query at position {}: {}", self.pos, message)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// Consumes the `expected` text, if it is next
+    fn eat(&mut self, expected: &str) -> bool {
+        self.skip_spaces();
+        let end = self.pos + expected.chars().count();
+        if end <= self.chars.len()
+            && self.chars[self.pos..end]
+                .iter()
+                .copied()
+                .eq(expected.chars())
+        {
+            self.pos = end;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<()> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("'{}' is expected", expected)))
+        }
+    }
+
+    /// pipe := term ('|' term)*
+    fn pipe(&mut self) -> Result<Filter> {
+        let mut filters = vec![self.term()?];
+        while self.eat("|") {
+            filters.push(self.term()?);
+        }
+        if filters.len() == 1 {
+            Ok(filters.remove(0))
+        } else {
+            Ok(Filter::Pipe(filters))
+        }
+    }
+
+    /// term := path | 'keys' | 'length' | 'select(' path op literal ')'
+    fn term(&mut self) -> Result<Filter> {
+        self.skip_spaces();
+        match self.peek() {
+            Some('.') => self.path(),
+            Some(c) if c.is_alphabetic() => {
+                let name = self.identifier();
+                match name.as_str() {
+                    "keys" => Ok(Filter::Keys),
+                    "length" => Ok(Filter::Length),
+                    "select" => {
+                        self.expect("(")?;
+                        let lhs = self.pipe()?;
+                        let comparison = self.comparison()?;
+                        let rhs = self.literal()?;
+                        self.expect(")")?;
+                        Ok(Filter::Select(Box::new(lhs), comparison, rhs))
+                    }
+                    _ => Err(self.error(&format!("unknown function '{}'", name))),
+                }
+            }
+            _ => Err(self.error("a path, `keys`, `length` or `select` is expected")),
+        }
+    }
+
+    /// path := '.' | ('.' key | '.'? '[' index ']')+ where each segment may be followed by '?'
+    fn path(&mut self) -> Result<Filter> {
+        let mut segments = Vec::new();
+        loop {
+            let dot = self.peek() == Some('.');
+            if dot {
+                self.pos += 1;
+            }
+            let segment = match self.peek() {
+                Some('[') => {
+                    self.pos += 1;
+                    self.skip_spaces();
+                    let segment = match self.peek() {
+                        Some(']') => Segment::Iterate(false),
+                        Some('"') => Segment::Key(self.string()?, false),
+                        _ => Segment::Index(self.integer()?, false),
+                    };
+                    self.expect("]")?;
+                    segment
+                }
+                Some('"') if dot => Segment::Key(self.string()?, false),
+                Some(c) if dot && (c.is_alphanumeric() || c == '_') => {
+                    Segment::Key(self.identifier(), false)
+                }
+                _ if dot && segments.is_empty() => break,
+                _ if dot => return Err(self.error("a key is expected after '.'")),
+                _ => break,
+            };
+            let optional = self.peek() == Some('?');
+            if optional {
+                self.pos += 1;
+            }
+            segments.push(match segment {
+                Segment::Key(key, _) => Segment::Key(key, optional),
+                Segment::Index(index, _) => Segment::Index(index, optional),
+                Segment::Iterate(_) => Segment::Iterate(optional),
+            });
+        }
+        if segments.is_empty() {
+            Ok(Filter::Identity)
+        } else {
+            Ok(Filter::Path(segments))
+        }
+    }
+
+    fn identifier(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '-')
+        {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn integer(&mut self) -> Result<i64> {
+        self.skip_spaces();
+        let start = self.pos;
+        if self.peek() == Some('-') {
+            self.pos += 1;
+        }
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        text.parse()
+            .map_err(|_| self.error("an index or a string key is expected"))
+    }
+
+    /// A JSON string literal
+    fn string(&mut self) -> Result<String> {
+        let start = self.pos;
+        self.pos += 1;
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                '\\' => self.pos += 1,
+                '"' => {
+                    let text: String = self.chars[start..self.pos].iter().collect();
+                    return serde_json::from_str(&text)
+                        .map_err(|err| self.error(&format!("invalid string: {}", err)));
+                }
+                _ => {}
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn comparison(&mut self) -> Result<Comparison> {
+        // The longer operators go first
+        for (text, comparison) in [
+            ("==", Comparison::Eq),
+            ("!=", Comparison::Ne),
+            ("<=", Comparison::Le),
+            (">=", Comparison::Ge),
+            ("<", Comparison::Lt),
+            (">", Comparison::Gt),
+        ] {
+            if self.eat(text) {
+                return Ok(comparison);
+            }
+        }
+        Err(self.error("a comparison is expected"))
+    }
+
+    /// A JSON literal: a string, a number, `true`, `false` or `null`
+    fn literal(&mut self) -> Result<Value> {
+        self.skip_spaces();
+        if self.peek() == Some('"') {
+            return Ok(Value::String(self.string()?));
+        }
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || c == '.' || c == '-' || c == '+')
+        {
+            self.pos += 1;
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        serde_json::from_str(&text).map_err(|_| self.error("a literal is expected"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_query_paths() {
+        let value = json!({
+            "claims": [
+                { "id": "0x01", "fee": 10, "verifiers": ["0xa"] },
+                { "id": "0x02", "fee": 20, "verifiers": [] },
+            ],
+            "account-name": "alice",
+        });
+        assert_eq!(query(&value, ".").unwrap(), value);
+        assert_eq!(query(&value, ".claims[0].id").unwrap(), json!("0x01"));
+        assert_eq!(query(&value, ".claims[-1].fee").unwrap(), json!(20));
+        assert_eq!(
+            query(&value, ".claims[].id").unwrap(),
+            json!(["0x01", "0x02"])
+        );
+        assert_eq!(query(&value, ".\"account-name\"").unwrap(), json!("alice"));
+        assert_eq!(
+            query(&value, ".[\"account-name\"]").unwrap(),
+            json!("alice")
+        );
+        assert_eq!(query(&value, ".absent").unwrap(), Value::Null);
+        assert_eq!(
+            query(&value, "keys").unwrap(),
+            json!(["account-name", "claims"])
+        );
+        assert_eq!(query(&value, ".claims | length").unwrap(), json!(2));
+        assert!(query(&value, ".claims.id").is_err());
+        assert_eq!(query(&value, ".claims.id?").unwrap(), json!([]));
+    }
+
+    #[test]
+    fn test_query_select() {
+        let value = json!([
+            { "id": "0x01", "fee": 10 },
+            { "id": "0x02", "fee": 20 },
+        ]);
+        assert_eq!(
+            query(&value, ".[] | select(.fee > 15) | .id").unwrap(),
+            json!("0x02")
+        );
+        assert_eq!(
+            query(&value, ".[] | select(.id != \"0x02\") | .fee").unwrap(),
+            json!(10)
+        );
+        assert_eq!(
+            query(&value, ".[] | select(.fee >= 10) | .id").unwrap(),
+            json!(["0x01", "0x02"])
+        );
+        assert!(parse(".[] | select(.fee ~ 10)").is_err());
+        assert!(parse(".claims |").is_err());
+    }
+}
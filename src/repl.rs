@@ -6,8 +6,11 @@ use crate::configs::CliMode;
 use crate::configs::Config;
 use crate::configs::Configs;
 use crate::execute::execute_command;
+use crate::output::OutputFormat;
+use crate::output::render;
 use crate::rpc_client::RpcClient;
 use crate::rpc_client::RpcClientError;
+use crate::rpc_client::RpcClientInterface;
 use crate::rpc_server::DOCKERFILE_IMAGE;
 use crate::rpc_server::DOCKERFILE_IMAGE_LOCAL;
 use crate::rpc_server::local_server_is_running;
@@ -33,6 +36,7 @@ use rustyline_derive::Helper;
 use rustyline_derive::Highlighter;
 use rustyline_derive::Hinter;
 use rustyline_derive::Validator;
+use std::process::ExitCode;
 use std::thread;
 use std::time::Duration;
 
@@ -125,11 +129,55 @@ fn print_repl_help() {
 
 type OutputResultFn = Box<dyn FnMut(anyhow::Result<Value, RpcClientError>)>;
 
+/// Filters the value of a command result with the `query` and renders it in the output format
+/// of the `config`.
+fn render_result(
+    config: &Config,
+    query: Option<&str>,
+    result: anyhow::Result<Value, RpcClientError>,
+) -> anyhow::Result<Value, RpcClientError> {
+    let value = match query {
+        Some(query) => crate::query::query(&result?, query)
+            .map_err(|err| RpcClientError::IncorrectRequest(err.to_string()))?,
+        None => result?,
+    };
+    Ok(Value::String(render(&value, config.output)))
+}
+
+/// Executes a command and renders its result. The `query` is set in the `config` before
+/// the command runs, so that the command returns the data, which the query filters.
+fn run_command<T: RpcClientInterface>(
+    config: &mut Config,
+    command: &Commands,
+    query: Option<&str>,
+    rpc_client: &mut T,
+) -> anyhow::Result<Value, RpcClientError> {
+    config.query = query.map(str::to_string);
+    let result = execute_command(config, command, rpc_client);
+    render_result(config, query, result)
+}
+
+/// Splits the REPL input into a command and a query after the first `|` outside of quotes.
+fn split_query(input: &str) -> (&str, Option<&str>) {
+    let mut quote = None;
+    for (i, c) in input.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '|') => return (input[..i].trim(), Some(input[i + 1..].trim())),
+            _ => {}
+        }
+    }
+    (input, None)
+}
+
 fn run_repl_loop(
     config: &mut Config,
     rpc_client: &mut RpcClient,
     print_commands: bool,
     local_docker: bool,
+    output: Option<OutputFormat>,
+    dry_run: bool,
     mut output_fn: OutputResultFn,
 ) -> anyhow::Result<Value, RpcClientError> {
     if local_docker {
@@ -207,9 +255,10 @@ fn run_repl_loop(
                         )))
                     })?,
                     _ => {
-                        // Parse the input as command line arguments
-                        match parse_repl_command(input) {
-                            Ok(command) => {
+                        // Parse the input as command line arguments, the query goes after `|`
+                        let (command, query) = split_query(input);
+                        match parse_repl_command(command) {
+                            Ok(cli) => {
                                 // Add to history (but don't add duplicates or special commands)
                                 if !input.is_empty() && input != "help" {
                                     rl.add_history_entry(input).map_err(|e| {
@@ -219,11 +268,15 @@ fn run_repl_loop(
                                         ))
                                     })?;
                                 }
-                                // Execute the parsed command
-                                output_fn(execute_command(config, &command, rpc_client));
+                                // Execute the parsed command, the output format of the session
+                                // may be overridden for a single command
+                                config.output = cli.output.or(output).unwrap_or_default();
+                                config.dry_run = cli.dry_run || dry_run;
+                                let query = query.or(cli.query.as_deref());
+                                output_fn(run_command(config, &cli.command, query, rpc_client));
                             }
                             Err(err) => {
-                                println!("{}", err);
+                                eprintln!("{}", err);
                                 print_repl_help();
                             }
                         }
@@ -251,7 +304,7 @@ fn run_repl_loop(
     Ok(Value::String("REPL session ended".to_string()))
 }
 
-fn parse_repl_command(input: &str) -> Result<Commands, String> {
+fn parse_repl_command(input: &str) -> Result<Cli, String> {
     // Split the input into arguments, handling quotes properly
     let args = match shlex::split(input) {
         Some(args) => args,
@@ -264,18 +317,21 @@ fn parse_repl_command(input: &str) -> Result<Commands, String> {
 
     // Parse using clap
     match Cli::try_parse_from(full_args) {
-        Ok(cli) => Ok(cli.command),
+        Ok(cli) => Ok(cli),
         Err(err) => Err(err.to_string()),
     }
 }
 
+/// Executes the command and returns the exit code of `vsl-cli`: it is non-zero, if a single
+/// command fails. The errors of the commands in REPL are only reported by `output_fn`.
 pub fn exec_command(
-    command: Commands,
+    cli: Cli,
     mut output_fn: OutputResultFn,
     command_str: Option<String>,
-) -> Result<()> {
+) -> Result<ExitCode> {
     // Create the client connection
     let mut rpc_client = RpcClient::new();
+    let command = cli.command;
 
     match command {
         Commands::Repl {
@@ -291,6 +347,8 @@ pub fn exec_command(
                 &mut rpc_client,
                 print_commands,
                 local_docker,
+                cli.output,
+                cli.dry_run,
                 output_fn,
             );
         }
@@ -302,8 +360,53 @@ pub fn exec_command(
                 Some(str) => output_fn(Ok(Value::String(str))),
                 None => {}
             }
-            output_fn(execute_command(&mut config, &command, &mut rpc_client))
+            config.output = cli.output.unwrap_or_default();
+            config.dry_run = cli.dry_run;
+            let result = run_command(&mut config, &command, cli.query.as_deref(), &mut rpc_client);
+            let exit_code = match &result {
+                Ok(_) => ExitCode::SUCCESS,
+                Err(err) => ExitCode::from(err.exit_code()),
+            };
+            output_fn(result);
+            return Ok(exit_code);
         }
     }
-    Ok(())
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_command_query() {
+        let mut config = Config::new("test".to_string(), None, CliMode::MultiCommand);
+        let mut rpc_client = RpcClient::new();
+        let address = "0xdb4a76394d34e39802ee169ec9527b9223a16f0f";
+        let add = Commands::AddressAdd {
+            name: "bob".to_string(),
+            address: address.to_string(),
+        };
+        assert!(run_command(&mut config, &add, None, &mut rpc_client).is_ok());
+        let show = Commands::AddressShow {
+            name: "bob".to_string(),
+            json: false,
+        };
+        // The query applies to the data even with the default text output
+        let result = run_command(&mut config, &show, Some(".ids[0]"), &mut rpc_client).unwrap();
+        assert!(
+            result
+                .as_str()
+                .is_some_and(|ids| ids.to_lowercase().contains(address))
+        );
+        // Without a query the text is formatted for a human
+        config.query = Some(".ids".to_string());
+        let result = run_command(&mut config, &show, None, &mut rpc_client).unwrap();
+        assert!(
+            result
+                .as_str()
+                .is_some_and(|text| text.starts_with("bob: "))
+        );
+        assert_eq!(config.query, None);
+    }
 }
@@ -48,6 +48,34 @@ pub enum RpcClientError {
     GeneralError(String),
 }
 
+/// The exit code of `vsl-cli` on a general error.
+pub const EXIT_CODE_GENERAL_ERROR: u8 = 1;
+/// The exit code of `vsl-cli` on an incorrect request, i.e. a usage error.
+pub const EXIT_CODE_INCORRECT_REQUEST: u8 = 2;
+/// The exit code of `vsl-cli`, when the network is unknown.
+pub const EXIT_CODE_NETWORK_IS_ABSENT: u8 = 3;
+/// The exit code of `vsl-cli`, when a node can't be reached.
+pub const EXIT_CODE_CONNECTION_ERROR: u8 = 4;
+/// The exit code of `vsl-cli`, when a node rejects the request.
+pub const EXIT_CODE_REJECTED: u8 = 5;
+/// The exit code of `vsl-cli` on an incorrect response of a node.
+pub const EXIT_CODE_INCORRECT_RESPONSE: u8 = 6;
+
+impl RpcClientError {
+    /// The exit code of the `vsl-cli` process, which failed with this error.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            RpcClientError::NetworkIsAbsent(_) => EXIT_CODE_NETWORK_IS_ABSENT,
+            RpcClientError::IncorrectRequest(_) => EXIT_CODE_INCORRECT_REQUEST,
+            RpcClientError::IncorrectResponse(_) => EXIT_CODE_INCORRECT_RESPONSE,
+            // The node has responded with an error object: the request is rejected
+            RpcClientError::ConnectionError(ConnectionError::Call(_)) => EXIT_CODE_REJECTED,
+            RpcClientError::ConnectionError(_) => EXIT_CODE_CONNECTION_ERROR,
+            RpcClientError::GeneralError(_) => EXIT_CODE_GENERAL_ERROR,
+        }
+    }
+}
+
 impl From<serde_json::Error> for RpcClientError {
     fn from(err: serde_json::Error) -> Self {
         RpcClientError::GeneralError(err.to_string())
@@ -0,0 +1,116 @@
+#![allow(unused)]
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use jsonschema::Validator;
+use serde_json::Value;
+use std::fs;
+
+/// Compiles a JSON Schema: the schema is checked against the meta-schema of its draft
+/// (2020-12, unless `$schema` names another one), its `$ref`s are resolved and its patterns
+/// are compiled. The references to other documents are not fetched.
+pub fn compile(schema: &Value) -> Result<Validator> {
+    jsonschema::validator_for(schema)
+        .map_err(|err| anyhow!("{}: {}", path(&err.instance_path), err))
+}
+
+/// Checks that a JSON Schema is valid, see `compile`.
+pub fn check_schema(schema: &Value) -> Result<()> {
+    compile(schema).map(|_| ())
+}
+
+/// Reads a JSON Schema from a file and checks that it is valid.
+pub fn read_schema(file: &str) -> Result<Value> {
+    let content = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read the schema from '{}'", file))?;
+    let schema: Value = serde_json::from_str(&content)
+        .with_context(|| format!("Invalid JSON of the schema in '{}'", file))?;
+    check_schema(&schema).with_context(|| format!("Invalid schema in '{}'", file))?;
+    Ok(schema)
+}
+
+/// Validates a value against a JSON Schema. Returns the list of violations, it is empty
+/// for a valid value.
+pub fn validate(schema: &Value, value: &Value) -> Vec<String> {
+    match compile(schema) {
+        Ok(validator) => validator
+            .iter_errors(value)
+            .map(|err| format!("{}: {}", path(&err.instance_path), err))
+            .collect(),
+        Err(err) => vec![format!("$: the schema is invalid: {}", err)],
+    }
+}
+
+/// Renders a JSON pointer to a value as a path from its root `$`, i.e. `/tags/0` as `$.tags.0`
+fn path(pointer: &impl ToString) -> String {
+    let pointer = pointer.to_string();
+    format!("${}", pointer.replace('/', "."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_check_schema() {
+        assert!(check_schema(&json!(true)).is_ok());
+        assert!(check_schema(&json!({ "type": ["string", "null"] })).is_ok());
+        assert!(check_schema(&json!({ "type": "text" })).is_err());
+        assert!(check_schema(&json!({ "properties": { "a": { "pattern": "(" } } })).is_err());
+        assert!(check_schema(&json!({ "anyOf": { "type": "string" } })).is_err());
+        assert!(check_schema(&json!(1)).is_err());
+        assert!(check_schema(&json!({ "title": "Age", "type": "integer", "minimum": 18 })).is_ok());
+        assert!(
+            check_schema(&json!({
+                "$ref": "#/$defs/age",
+                "$defs": { "age": { "type": "integer" } },
+            }))
+            .is_ok()
+        );
+        assert!(check_schema(&json!({ "items": { "type": "string", "format": "email" } })).is_ok());
+        assert!(check_schema(&json!({ "maxLength": -1 })).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let schema = json!({
+            "type": "object",
+            "required": ["subject", "age"],
+            "properties": {
+                "subject": { "type": "string", "pattern": "^0x[0-9a-fA-F]{40}$" },
+                "age": { "$ref": "#/$defs/age" },
+                "tags": { "type": "array", "items": { "enum": ["kyc", "aml"] }, "maxItems": 2 },
+            },
+            "patternProperties": { "^x-": { "type": "string" } },
+            "additionalProperties": false,
+            "$defs": { "age": { "type": "integer", "minimum": 18 } },
+        });
+        let subject = "0x749ab3318b74907f6e5856ce9ce1f3b55e3bb38a";
+        assert!(
+            validate(
+                &schema,
+                &json!({ "subject": subject, "age": 21, "tags": ["kyc"], "x-note": "ok" })
+            )
+            .is_empty()
+        );
+        let errors = validate(
+            &schema,
+            &json!({ "subject": "bob", "age": 17, "tags": ["kyc", "ssn"], "extra": 1 }),
+        );
+        assert_eq!(errors.len(), 4, "{:?}", errors);
+        for path in ["$.age: ", "$.subject: ", "$.tags.1: ", "$: "] {
+            assert!(
+                errors.iter().any(|err| err.starts_with(path)),
+                "{:?}",
+                errors
+            );
+        }
+        assert_eq!(validate(&schema, &json!({ "subject": subject })).len(), 1);
+        assert_eq!(validate(&schema, &json!("text")).len(), 1);
+        let one_of = json!({ "oneOf": [{ "type": "integer" }, { "type": "number" }] });
+        assert!(!validate(&one_of, &json!(1)).is_empty());
+        assert!(validate(&one_of, &json!(1.5)).is_empty());
+    }
+}
@@ -0,0 +1,257 @@
+#![allow(unused)]
+
+use alloy::primitives::Address;
+use alloy::primitives::B256;
+use alloy::primitives::ChainId;
+use alloy::primitives::Signature;
+use alloy::signers::Error as SignerError;
+use alloy::signers::SignerSync;
+use alloy::signers::local::PrivateKeySigner;
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::params::ObjectParams;
+use jsonrpsee::http_client::HttpClient;
+use serde_json::Value;
+use serde_json::json;
+use std::io::Write as _;
+use std::process::Command;
+use std::process::Stdio;
+use std::str::FromStr as _;
+use tokio::runtime::Runtime;
+
+/// The JSON-RPC method of a remote signer, which signs a hash on behalf of an account.
+pub const REMOTE_SIGNER_METHOD: &str = "signer_signHash";
+
+/// The signer of an account messages. Every backend implements the `alloy` signer trait,
+/// so it may be used wherever a message is signed with `into_signed`.
+#[derive(Debug, Clone)]
+pub enum AccountSigner {
+    /// The private key is held in memory: it is stored plain, or decrypted from a keystore.
+    Local(PrivateKeySigner),
+    /// The signing is delegated to an external process, the private key is never seen by `vsl-cli`.
+    External(ExternalSigner),
+    /// The signing is delegated to a signing service over JSON-RPC.
+    Remote(RemoteSigner),
+}
+
+impl AccountSigner {
+    /// The address of the account, which signs messages.
+    pub fn address(&self) -> Address {
+        match self {
+            AccountSigner::Local(signer) => signer.address(),
+            AccountSigner::External(signer) => signer.address,
+            AccountSigner::Remote(signer) => signer.address,
+        }
+    }
+}
+
+impl SignerSync for AccountSigner {
+    fn sign_hash_sync(&self, hash: &B256) -> Result<Signature, SignerError> {
+        match self {
+            AccountSigner::Local(signer) => signer.sign_hash_sync(hash),
+            AccountSigner::External(signer) => signer.sign_hash_sync(hash),
+            AccountSigner::Remote(signer) => signer.sign_hash_sync(hash),
+        }
+    }
+    fn chain_id_sync(&self) -> Option<ChainId> {
+        None
+    }
+}
+
+/// The signer, which runs an external process for each signature.
+///
+/// The process receives a JSON object `{"address": "0x..", "hash": "0x.."}` on its standard input,
+/// and must print a JSON object `{"signature": "0x.."}` (or `{"error": ".."}`) to its standard output.
+#[derive(Debug, Clone)]
+pub struct ExternalSigner {
+    /// The command line of the signer process: the program and its arguments
+    pub command: Vec<String>,
+    /// The address of the account, the signatures are checked against it
+    pub address: Address,
+}
+
+fn signer_error(message: String) -> SignerError {
+    SignerError::other(message)
+}
+
+impl ExternalSigner {
+    /// Runs the signer process and returns its response.
+    fn request(&self, request: &Value) -> Result<Value, SignerError> {
+        let (program, args) = self.command.split_first().ok_or(signer_error(
+            "The external signer command is empty".to_string(),
+        ))?;
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|err| {
+                signer_error(format!(
+                    "Failed to start the external signer '{}': {}",
+                    program, err
+                ))
+            })?;
+        child
+            .stdin
+            .take()
+            .ok_or(signer_error(
+                "Failed to open the external signer input".to_string(),
+            ))?
+            .write_all(format!("{}\n", request).as_bytes())
+            .map_err(|err| {
+                signer_error(format!("Failed to write to the external signer: {}", err))
+            })?;
+        let output = child
+            .wait_with_output()
+            .map_err(|err| signer_error(format!("Failed to run the external signer: {}", err)))?;
+        if !output.status.success() {
+            return Err(signer_error(format!(
+                "The external signer failed with status: {}",
+                output.status
+            )));
+        }
+        serde_json::from_slice(&output.stdout).map_err(|err| {
+            signer_error(format!(
+                "The external signer response is not a JSON: {}",
+                err
+            ))
+        })
+    }
+}
+
+impl SignerSync for ExternalSigner {
+    fn sign_hash_sync(&self, hash: &B256) -> Result<Signature, SignerError> {
+        let request = json!({
+            "address": self.address.to_string().to_ascii_lowercase(),
+            "hash": hash.to_string(),
+        });
+        let response = self.request(&request)?;
+        if let Some(error) = response.get("error") {
+            return Err(signer_error(format!(
+                "The external signer rejected the request: {}",
+                error
+            )));
+        }
+        let signature = match response.get("signature") {
+            Some(Value::String(signature)) => Signature::from_str(signature)?,
+            _ => {
+                return Err(signer_error(format!(
+                    "The external signer response must contain the `signature` string, got: {}",
+                    response
+                )));
+            }
+        };
+        check_signature(&signature, hash, &self.address)?;
+        Ok(signature)
+    }
+    fn chain_id_sync(&self) -> Option<ChainId> {
+        None
+    }
+}
+
+/// The signer, which delegates signing to a signing service over JSON-RPC.
+///
+/// The service is called with the method `signer_signHash` and the parameters
+/// `{"address": "0x..", "hash": "0x.."}`, and must return the signature as a hex string.
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    /// The URL of the signing service
+    pub url: String,
+    /// The address of the account, the signatures are checked against it
+    pub address: Address,
+}
+
+impl SignerSync for RemoteSigner {
+    fn sign_hash_sync(&self, hash: &B256) -> Result<Signature, SignerError> {
+        let client = HttpClient::builder().build(&self.url).map_err(|err| {
+            signer_error(format!(
+                "Failed to connect to the remote signer {}: {}",
+                self.url, err
+            ))
+        })?;
+        let mut params = ObjectParams::new();
+        params
+            .insert("address", self.address.to_string().to_ascii_lowercase())
+            .map_err(|err| signer_error(err.to_string()))?;
+        params
+            .insert("hash", hash.to_string())
+            .map_err(|err| signer_error(err.to_string()))?;
+        let runtime = Runtime::new().map_err(|err| signer_error(err.to_string()))?;
+        let response = runtime
+            .block_on(client.request::<String, ObjectParams>(REMOTE_SIGNER_METHOD, params))
+            .map_err(|err| {
+                signer_error(format!(
+                    "The remote signer {} failed to sign: {}",
+                    self.url, err
+                ))
+            })?;
+        let signature = Signature::from_str(&response)?;
+        check_signature(&signature, hash, &self.address)?;
+        Ok(signature)
+    }
+    fn chain_id_sync(&self) -> Option<ChainId> {
+        None
+    }
+}
+
+/// Checks that the `signature` of the `hash` is made by the `address`.
+pub fn check_signature(
+    signature: &Signature,
+    hash: &B256,
+    address: &Address,
+) -> Result<(), SignerError> {
+    let signer = signature.recover_address_from_prehash(hash)?;
+    if signer != *address {
+        return Err(signer_error(format!(
+            "The signature is made by {}, expected {}",
+            signer, address
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIVATE_KEY: &str = "b6dd863bea551b5bb27ce9917316a01ea4c331f24e0e4fe56e28eb430f175ed7";
+
+    /// The stub of an external signer: ignores the input and prints the `response`.
+    #[cfg(unix)]
+    fn stub_signer(response: &Value, address: Address) -> ExternalSigner {
+        ExternalSigner {
+            command: vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                format!("cat > /dev/null; echo '{}'", response),
+            ],
+            address: address,
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_external_signer() {
+        let local = PrivateKeySigner::from_str(PRIVATE_KEY).expect("must be a valid key");
+        let hash = B256::repeat_byte(0x42);
+        let signature = local.sign_hash_sync(&hash).expect("must sign");
+
+        let external = stub_signer(
+            &json!({ "signature": signature.to_string() }),
+            local.address(),
+        );
+        assert_eq!(
+            external.sign_hash_sync(&hash).expect("must sign"),
+            signature
+        );
+
+        let impostor = stub_signer(
+            &json!({ "signature": signature.to_string() }),
+            Address::repeat_byte(0x01),
+        );
+        assert!(impostor.sign_hash_sync(&hash).is_err());
+
+        let rejecting = stub_signer(&json!({ "error": "denied" }), local.address());
+        assert!(rejecting.sign_hash_sync(&hash).is_err());
+    }
+}
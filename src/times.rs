@@ -0,0 +1,249 @@
+#![allow(unused)]
+
+use anyhow::Result;
+use anyhow::anyhow;
+use chrono::DateTime;
+use chrono::SecondsFormat;
+use serde_json::Value;
+
+/// The keys of numeric fields in the node responses, which hold Unix timestamps in seconds.
+const TIMESTAMP_KEYS: [&str; 6] = [
+    "expires",
+    "timestamp",
+    "since",
+    "submitted_at",
+    "settled_at",
+    "created_at",
+];
+
+/// Parses a duration: a number of seconds, or a sequence of numbers with units, like `90m`,
+/// `3d` or `1h30m`. The units are `s`, `m`, `h`, `d` and `w`.
+pub fn parse_duration(s: &str) -> Result<u64> {
+    let s = s.trim();
+    if let Ok(seconds) = s.parse::<u64>() {
+        return Ok(seconds);
+    }
+    let invalid = || {
+        anyhow!(
+            "Invalid duration: '{}', must be a number of seconds, or like `90m`, `3d`, `1h30m`",
+            s
+        )
+    };
+    let mut total: u64 = 0;
+    let mut number = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 24 * 60 * 60,
+            'w' => 7 * 24 * 60 * 60,
+            _ => return Err(invalid()),
+        };
+        let value: u64 = number.parse().map_err(|_| invalid())?;
+        total = value
+            .checked_mul(unit)
+            .and_then(|seconds| total.checked_add(seconds))
+            .ok_or_else(invalid)?;
+        number.clear();
+    }
+    if !number.is_empty() || s.is_empty() {
+        return Err(invalid());
+    }
+    Ok(total)
+}
+
+/// Parses a timestamp to Unix seconds. Accepted are:
+/// - Unix seconds, like `1760961600`
+/// - RFC3339 dates, like `2026-10-20T12:00:00Z`
+/// - expressions relative to `now`, like `now`, `now+2h`, `now-30m`, `+2h` or `-30m`
+pub fn parse_timestamp(s: &str, now: u64) -> Result<u64> {
+    let s = s.trim();
+    if let Ok(seconds) = s.parse::<u64>() {
+        return Ok(seconds);
+    }
+    let relative = s.strip_prefix("now").unwrap_or(s);
+    if relative.is_empty() {
+        return Ok(now);
+    }
+    if let Some(duration) = relative.strip_prefix('+') {
+        return now
+            .checked_add(parse_duration(duration)?)
+            .ok_or(anyhow!("Invalid timestamp: '{}', it is too far", s));
+    }
+    if let Some(duration) = relative.strip_prefix('-') {
+        return now
+            .checked_sub(parse_duration(duration)?)
+            .ok_or(anyhow!("Invalid timestamp: '{}', it is before 1970", s));
+    }
+    let date = DateTime::parse_from_rfc3339(s).map_err(|err| {
+        anyhow!(
+            "Invalid timestamp: '{}', must be Unix seconds, RFC3339 date like `2026-10-20T12:00:00Z`, or relative like `now+2h`: {}",
+            s,
+            err
+        )
+    })?;
+    u64::try_from(date.timestamp()).or(Err(anyhow!(
+        "Invalid timestamp: '{}', it is before 1970",
+        s
+    )))
+}
+
+/// Formats Unix seconds as an RFC3339 date in UTC, like `2026-10-20T12:00:00Z`.
+pub fn format_timestamp(seconds: u64) -> String {
+    i64::try_from(seconds)
+        .ok()
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        .map(|date| date.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .unwrap_or(seconds.to_string())
+}
+
+/// The expiration time of a claim: the `expires` timestamp, or `lifetime` after now.
+/// The expiration time must be in the future.
+pub fn expiration_time(expires: Option<&str>, lifetime: Option<&str>, now: u64) -> Result<u64> {
+    let expires = match (expires, lifetime) {
+        (Some(expires), _) => parse_timestamp(expires, now)?,
+        (None, Some(lifetime)) => now
+            .checked_add(parse_duration(lifetime)?)
+            .ok_or(anyhow!("Lifetime '{}' is too long", lifetime))?,
+        (None, None) => {
+            return Err(anyhow!(
+                "Expiration time is not set: both `--expires` and `--lifetime` are not set"
+            ));
+        }
+    };
+    if expires <= now {
+        return Err(anyhow!(
+            "Expiration time {} has already passed",
+            format_timestamp(expires)
+        ));
+    }
+    Ok(expires)
+}
+
+/// The start time of a query: the `since` timestamp, or `within` before now.
+/// The start time must not be in the future.
+pub fn since_time(since: Option<&str>, within: Option<&str>, now: u64) -> Result<u64> {
+    let since = match (since, within) {
+        (Some(since), _) => parse_timestamp(since, now)?,
+        (None, Some(within)) => now.saturating_sub(parse_duration(within)?),
+        (None, None) => {
+            return Err(anyhow!(
+                "When quering claims the since value is not set: both `--since` and `--within` are not set"
+            ));
+        }
+    };
+    if since > now {
+        return Err(anyhow!(
+            "Since time {} is in the future, query makes no sense",
+            format_timestamp(since)
+        ));
+    }
+    Ok(since)
+}
+
+/// Replaces the timestamps in a node response with RFC3339 dates. The timestamps are
+/// the objects `{"seconds": .., "nanos": ..}`, and the numbers under the known keys, like `expires`.
+pub fn humanize_timestamps(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let seconds = match value {
+                    Value::Number(number) if TIMESTAMP_KEYS.contains(&key.as_str()) => {
+                        number.as_u64()
+                    }
+                    _ => timestamp_seconds(value),
+                };
+                match seconds {
+                    Some(seconds) => *value = Value::String(format_timestamp(seconds)),
+                    None => humanize_timestamps(value),
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(humanize_timestamps),
+        _ => {}
+    }
+}
+
+/// The seconds of a timestamp object: `{"seconds": ..}` or `{"seconds": .., "nanos": ..}`
+fn timestamp_seconds(value: &Value) -> Option<u64> {
+    let map = value.as_object()?;
+    let only_time_keys = map.keys().all(|key| key == "seconds" || key == "nanos");
+    if only_time_keys {
+        map.get("seconds")?.as_u64()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const NOW: u64 = 1_760_961_600; // 2025-10-20T12:00:00Z
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("3600").unwrap(), 3600);
+        assert_eq!(parse_duration("90m").unwrap(), 90 * 60);
+        assert_eq!(parse_duration("3d").unwrap(), 3 * 24 * 3600);
+        assert_eq!(parse_duration("1h30m").unwrap(), 5400);
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("1x").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("10m5").is_err());
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("1760961600", NOW).unwrap(), NOW);
+        assert_eq!(parse_timestamp("2025-10-20T12:00:00Z", NOW).unwrap(), NOW);
+        assert_eq!(
+            parse_timestamp("2025-10-20T14:00:00+02:00", NOW).unwrap(),
+            NOW
+        );
+        assert_eq!(parse_timestamp("now", NOW).unwrap(), NOW);
+        assert_eq!(parse_timestamp("now+2h", NOW).unwrap(), NOW + 7200);
+        assert_eq!(parse_timestamp("-30m", NOW).unwrap(), NOW - 1800);
+        assert!(parse_timestamp("tomorrow", NOW).is_err());
+        assert_eq!(format_timestamp(NOW), "2025-10-20T12:00:00Z");
+    }
+
+    #[test]
+    fn test_claim_times() {
+        assert_eq!(expiration_time(None, Some("1h"), NOW).unwrap(), NOW + 3600);
+        assert_eq!(
+            expiration_time(Some("now+1d"), Some("1h"), NOW).unwrap(),
+            NOW + 24 * 3600
+        );
+        assert!(expiration_time(Some("-1m"), None, NOW).is_err());
+        assert!(expiration_time(None, None, NOW).is_err());
+        assert_eq!(since_time(None, Some("1h"), NOW).unwrap(), NOW - 3600);
+        assert_eq!(
+            since_time(Some("2025-10-20T11:00:00Z"), Some("1h"), NOW).unwrap(),
+            NOW - 3600
+        );
+        assert!(since_time(Some("+1h"), None, NOW).is_err());
+    }
+
+    #[test]
+    fn test_humanize_timestamps() {
+        let mut value = json!([
+            { "claim": "a", "expires": { "seconds": NOW, "nanos": 0 } },
+            { "claim": "b", "expires": NOW, "fee": 10 },
+        ]);
+        humanize_timestamps(&mut value);
+        assert_eq!(
+            value,
+            json!([
+                { "claim": "a", "expires": "2025-10-20T12:00:00Z" },
+                { "claim": "b", "expires": "2025-10-20T12:00:00Z", "fee": 10 },
+            ])
+        );
+    }
+}
@@ -0,0 +1,383 @@
+#![allow(unused)]
+
+use alloy::primitives::Address;
+use alloy::primitives::B256;
+use alloy::primitives::Signature;
+use alloy::primitives::U256;
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+use std::fs;
+use std::str::FromStr as _;
+use vsl_sdk::rpc_messages::CreateAssetMessage;
+use vsl_sdk::rpc_messages::PayMessage;
+use vsl_sdk::rpc_messages::SetStateMessage;
+use vsl_sdk::rpc_messages::SettleClaimMessage;
+use vsl_sdk::rpc_messages::SignedMessage;
+use vsl_sdk::rpc_messages::SubmittedClaim;
+use vsl_sdk::rpc_messages::TransferAssetMessage;
+
+/// A signed message, which is not sent to the network yet: the output of a command
+/// with `--sign-only` and the input of `tx:send`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SignedTransaction {
+    /// The RPC method, which accepts the message, i.e. `vsl_pay`
+    pub method: String,
+    /// The parameter of the method, which holds the message, i.e. `payment`
+    pub param: String,
+    /// The signed message, as it is sent to the network
+    pub signed: Value,
+}
+
+/// A type of signed messages
+#[derive(Clone, Copy, Debug)]
+pub struct MessageType {
+    /// The name of the message type in vsl-sdk, i.e. `PayMessage`
+    pub name: &'static str,
+    /// The RPC method, which accepts the messages
+    pub method: &'static str,
+    /// The parameter of the method, which holds the message
+    pub param: &'static str,
+    /// The field of the message, which holds the address of the sender
+    pub sender: &'static str,
+    /// The field, which only the messages of this type have
+    key: &'static str,
+    /// Checks the hash of a signed message of this type
+    check: fn(&Value) -> Result<()>,
+}
+
+/// The types of signed messages, which are accepted by the network. The order matters
+/// for `MessageType::of_message`: the more specific types go first.
+const MESSAGE_TYPES: [MessageType; 6] = [
+    MessageType {
+        name: "SettleClaimMessage",
+        method: "vsl_settleClaim",
+        param: "settled_claim",
+        sender: "from",
+        key: "target_claim_id",
+        check: check_signed::<SettleClaimMessage>,
+    },
+    MessageType {
+        name: "SubmittedClaim",
+        method: "vsl_submitClaim",
+        param: "claim",
+        sender: "from",
+        key: "claim",
+        check: check_signed::<SubmittedClaim>,
+    },
+    MessageType {
+        name: "CreateAssetMessage",
+        method: "vsl_createAsset",
+        param: "asset_data",
+        sender: "account_id",
+        key: "ticker_symbol",
+        check: check_signed::<CreateAssetMessage>,
+    },
+    MessageType {
+        name: "TransferAssetMessage",
+        method: "vsl_transferAsset",
+        param: "transfer_asset",
+        sender: "from",
+        key: "asset_id",
+        check: check_signed::<TransferAssetMessage>,
+    },
+    MessageType {
+        name: "SetStateMessage",
+        method: "vsl_setAccountState",
+        param: "state",
+        sender: "from",
+        key: "state",
+        check: check_signed::<SetStateMessage>,
+    },
+    MessageType {
+        name: "PayMessage",
+        method: "vsl_pay",
+        param: "payment",
+        sender: "from",
+        key: "amount",
+        check: check_signed::<PayMessage>,
+    },
+];
+
+impl MessageType {
+    /// The type of messages, which are accepted by the `method` in the `param`
+    pub fn of_method(method: &str, param: &str) -> Option<MessageType> {
+        MESSAGE_TYPES
+            .into_iter()
+            .find(|message_type| message_type.method == method && message_type.param == param)
+    }
+
+    /// The type of a message, recognized by its fields
+    pub fn of_message(message: &Value) -> Option<MessageType> {
+        let message = message.as_object()?;
+        MESSAGE_TYPES
+            .into_iter()
+            .find(|message_type| message.contains_key(message_type.key))
+    }
+}
+
+impl SignedTransaction {
+    pub fn new(method: &str, param: &str, signed: Value) -> Self {
+        SignedTransaction {
+            method: method.to_string(),
+            param: param.to_string(),
+            signed,
+        }
+    }
+
+    /// Parses a signed transaction from JSON and checks, that its method accepts signed messages
+    /// with the parameter.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let transaction: SignedTransaction = serde_json::from_str(json)
+            .context("Signed message must be a JSON object with `method`, `param` and `signed`")?;
+        if MessageType::of_method(&transaction.method, &transaction.param).is_none() {
+            return Err(anyhow!(
+                "Unknown signed message type: method '{}' with parameter '{}'",
+                transaction.method,
+                transaction.param
+            ));
+        }
+        Ok(transaction)
+    }
+
+    /// Reads a signed transaction from a file.
+    pub fn read(file: &str) -> Result<Self> {
+        let json = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read signed message from '{}'", file))?;
+        Self::from_json(&json)
+    }
+
+    /// The submitted claim of a `vsl_submitClaim` transaction
+    pub fn submitted_claim(&self) -> Option<SubmittedClaim> {
+        if self.method != "vsl_submitClaim" {
+            return None;
+        }
+        serde_json::from_value(self.signed.get("message")?.clone()).ok()
+    }
+
+    /// The address of the sender and the nonce of the signed message
+    pub fn sender_nonce(&self) -> Option<(String, u64)> {
+        let message_type = MessageType::of_method(&self.method, &self.param)?;
+        let message = self.signed.get("message")?;
+        let sender = address_of(message.get(message_type.sender)?)?;
+        let nonce = match message.get("nonce")? {
+            Value::String(nonce) => nonce.parse().ok()?,
+            nonce => nonce.as_u64()?,
+        };
+        Some((sender.to_string(), nonce))
+    }
+
+    /// Writes a signed transaction to a file as pretty-printed JSON.
+    pub fn write(&self, file: &str) -> Result<()> {
+        fs::write(file, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write signed message to '{}'", file))
+    }
+}
+
+/// Decodes a signed message: a signed transaction, or a signed message as is. The result
+/// holds the type and the fields of the message, the signer, recovered from the signature,
+/// and whether the signature is valid: the hash matches the message, and the signer is its sender.
+pub fn decode(value: &Value) -> Result<Value> {
+    let (signed, message_type) = match serde_json::from_value::<SignedTransaction>(value.clone()) {
+        Ok(transaction) => {
+            let message_type = MessageType::of_method(&transaction.method, &transaction.param)
+                .ok_or(anyhow!(
+                    "Unknown signed message type: method '{}' with parameter '{}'",
+                    transaction.method,
+                    transaction.param
+                ))?;
+            (transaction.signed, message_type)
+        }
+        Err(_) => {
+            let message_type = value
+                .get("message")
+                .and_then(MessageType::of_message)
+                .ok_or(anyhow!(
+                    "Unknown signed message type, the message must be one of: {}",
+                    MESSAGE_TYPES
+                        .iter()
+                        .map(|message_type| message_type.name)
+                        .collect::<Vec<&str>>()
+                        .join(", ")
+                ))?;
+            (value.clone(), message_type)
+        }
+    };
+    let message = signed.get("message").cloned().unwrap_or(Value::Null);
+    let sender = message.get(message_type.sender).and_then(address_of);
+    let signer = recover_signer(&signed);
+    let error = match (&signer, sender) {
+        (Err(err), _) => Some(err.to_string()),
+        (Ok(_), None) => Some(format!(
+            "The message has no sender address in `{}`",
+            message_type.sender
+        )),
+        (Ok(signer), Some(sender)) if *signer != sender => Some(format!(
+            "The message is signed by {}, but its sender is {}",
+            signer, sender
+        )),
+        _ => (message_type.check)(&signed)
+            .err()
+            .map(|err| err.to_string()),
+    };
+    let mut decoded = json!({
+        "type": message_type.name,
+        "method": message_type.method,
+        "message": message,
+        "signer": signer.ok().map(|signer| signer.to_string()),
+        "valid": error.is_none(),
+    });
+    if let Some(error) = error {
+        decoded["error"] = Value::String(error);
+    }
+    Ok(decoded)
+}
+
+/// Recovers the address of the signer of a signed message from its `hash` and the signature `r`, `s`, `v`.
+fn recover_signer(signed: &Value) -> Result<Address> {
+    let field = |name: &str| {
+        signed
+            .get(name)
+            .ok_or(anyhow!("The signed message has no `{}`", name))
+    };
+    let hex = |name: &str| -> Result<U256> {
+        match field(name)? {
+            Value::String(hex) => Ok(U256::from_str(hex)?),
+            Value::Number(number) => number
+                .as_u64()
+                .map(U256::from)
+                .ok_or(anyhow!("Invalid `{}` of the signed message", name)),
+            _ => Err(anyhow!("Invalid `{}` of the signed message", name)),
+        }
+    };
+    let hash = match field("hash")? {
+        Value::String(hash) => B256::from_str(hash)?,
+        _ => return Err(anyhow!("Invalid `hash` of the signed message")),
+    };
+    // `v` is either the parity itself, or the legacy 27 or 28
+    let v = hex("v")?;
+    let y_parity = if v >= U256::from(27u64) {
+        v == U256::from(28u64)
+    } else {
+        v == U256::from(1u64)
+    };
+    let signature = Signature::new(hex("r")?, hex("s")?, y_parity);
+    Ok(signature.recover_address_from_prehash(&hash)?)
+}
+
+/// The address in a message: a hex string, or an object with the `address` hex string
+fn address_of(value: &Value) -> Option<Address> {
+    match value {
+        Value::String(address) => Address::from_str(address).ok(),
+        Value::Object(map) => map.get("address").and_then(address_of),
+        _ => None,
+    }
+}
+
+/// Checks, that the hash of a signed message matches the message.
+fn check_signed<T>(signed: &Value) -> Result<()>
+where
+    SignedMessage<T>: for<'de> Deserialize<'de>,
+{
+    let signed: SignedMessage<T> = serde_json::from_value(signed.clone())?;
+    signed
+        .check_and_parse()
+        .map(|_| ())
+        .map_err(|err| anyhow!("The signature does not match the message: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::signers::local::PrivateKeySigner;
+    use vsl_sdk::IntoSigned as _;
+    use vsl_sdk::rpc_messages::VslAddress;
+
+    #[test]
+    fn test_signed_transaction_from_json() {
+        let transaction = SignedTransaction::new("vsl_pay", "payment", json!({ "nonce": "1" }));
+        let json = serde_json::to_string(&transaction).unwrap();
+        assert_eq!(SignedTransaction::from_json(&json).unwrap(), transaction);
+        assert!(
+            SignedTransaction::from_json(
+                r#"{"method": "vsl_pay", "param": "claim", "signed": {}}"#
+            )
+            .is_err()
+        );
+        assert!(SignedTransaction::from_json(r#"{"method": "vsl_pay"}"#).is_err());
+    }
+
+    #[test]
+    fn test_submitted_claim() {
+        let pay = SignedTransaction::new("vsl_pay", "payment", json!({ "message": {} }));
+        assert!(pay.submitted_claim().is_none());
+        let claim = SignedTransaction::new("vsl_submitClaim", "claim", json!({ "message": {} }));
+        assert!(claim.submitted_claim().is_none());
+    }
+
+    #[test]
+    fn test_sender_nonce() {
+        let from = "0x749ab3318b74907f6e5856ce9ce1f3b55e3bb38a";
+        let pay = SignedTransaction::new(
+            "vsl_pay",
+            "payment",
+            json!({ "message": { "from": from, "to": from, "amount": "0x1", "nonce": "7" } }),
+        );
+        let (sender, nonce) = pay.sender_nonce().unwrap();
+        assert!(sender.eq_ignore_ascii_case(from));
+        assert_eq!(nonce, 7);
+        let asset = SignedTransaction::new(
+            "vsl_createAsset",
+            "asset_data",
+            json!({ "message": { "from": from, "nonce": "7" } }),
+        );
+        assert!(asset.sender_nonce().is_none());
+    }
+
+    #[test]
+    fn test_message_type() {
+        let settle = json!({ "from": "0x01", "nonce": "1", "target_claim_id": "0x02" });
+        assert_eq!(
+            MessageType::of_message(&settle).map(|message_type| message_type.name),
+            Some("SettleClaimMessage")
+        );
+        let pay = json!({ "from": "0x01", "to": "0x02", "amount": "0x10", "nonce": "1" });
+        assert_eq!(
+            MessageType::of_message(&pay).map(|message_type| message_type.method),
+            Some("vsl_pay")
+        );
+        assert!(MessageType::of_message(&json!({ "nonce": "1" })).is_none());
+        for message_type in MESSAGE_TYPES {
+            assert!((message_type.check)(&json!({ "message": {} })).is_err());
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        let signer = PrivateKeySigner::random();
+        let address = signer.address().to_string();
+        let message = PayMessage {
+            from: VslAddress::from_str(&address).unwrap(),
+            to: VslAddress::from_str("0x0000000000000000000000000000000000000001").unwrap(),
+            amount: "0x10".to_string(),
+            nonce: "7".to_string(),
+        };
+        let signed = serde_json::to_value(message.into_signed(&signer).unwrap()).unwrap();
+        let decoded = decode(&signed).unwrap();
+        assert_eq!(decoded["type"], "PayMessage");
+        assert_eq!(decoded["signer"], Value::String(address));
+        assert_eq!(decoded["valid"], true);
+
+        let transaction = SignedTransaction::new("vsl_pay", "payment", signed.clone());
+        let decoded = decode(&serde_json::to_value(transaction).unwrap()).unwrap();
+        assert_eq!(decoded["valid"], true);
+
+        let mut tampered = signed;
+        tampered["message"]["amount"] = json!("0x1000");
+        assert_eq!(decode(&tampered).unwrap()["valid"], false);
+    }
+}
@@ -0,0 +1,404 @@
+#![allow(unused)]
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read as _;
+use std::io::Write as _;
+use std::path::Path;
+use std::process::Command;
+use std::process::Stdio;
+use std::time::Duration;
+use std::time::Instant;
+use vsl_sdk::rpc_messages::SubmittedClaim;
+use vsl_sdk::rpc_messages::VerifiedClaim;
+
+/// The claims, submitted before the last poll within this overlap, are requested again,
+/// so that a claim, which is listed by the node with a delay, is not missed.
+pub const POLL_OVERLAP: u64 = 60;
+
+/// The verdict of a verifier on a submitted claim
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    /// The checker has accepted the claim, but it is not settled yet
+    Accepted,
+    /// The claim is accepted and settled
+    Settled,
+    /// The checker has rejected the claim
+    Rejected,
+    /// The network has rejected the settlement of the claim
+    Failed,
+    /// The checker has failed, the claim is checked again on the next poll
+    Errored,
+}
+
+/// The record of a claim, processed by a verifier
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ClaimRecord {
+    pub verdict: Verdict,
+    /// The time of the verdict, Unix seconds
+    pub time: u64,
+    /// The id of the settlement
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settled_id: Option<String>,
+    /// The output of the checker, which has rejected the claim, or the checker, or settlement error
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// The submitted claim, which is checked again: it may be out of the poll window by then
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claim: Option<Value>,
+}
+
+impl ClaimRecord {
+    pub fn new(verdict: Verdict, time: u64) -> Self {
+        ClaimRecord {
+            verdict,
+            time,
+            settled_id: None,
+            reason: None,
+            claim: None,
+        }
+    }
+
+    /// The claim is processed and must not be checked, nor settled again
+    pub fn is_done(&self) -> bool {
+        !matches!(self.verdict, Verdict::Accepted | Verdict::Errored)
+    }
+}
+
+/// The options of a verifier, which polls the claims, submitted to it
+#[derive(Clone, Debug, PartialEq)]
+pub struct VerifierOptions {
+    /// The checker command, run with a shell for every claim
+    pub program: String,
+    /// How long the checker may run for a claim, seconds
+    pub program_timeout: u64,
+    /// Poll the claims, submitted since this time, instead of the time of the state, Unix seconds
+    pub since: Option<u64>,
+    /// The file of the verifier state
+    pub state_file: String,
+}
+
+/// The persistent progress of a verifier: the claims, it has processed, by their ids
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct VerifierState {
+    /// The address of the verifier account
+    pub address: String,
+    /// The claims, submitted since this time, are polled, Unix seconds
+    pub since: u64,
+    pub claims: HashMap<String, ClaimRecord>,
+}
+
+impl VerifierState {
+    pub fn new(address: &str, since: u64) -> Self {
+        VerifierState {
+            address: address.to_lowercase(),
+            since,
+            claims: HashMap::new(),
+        }
+    }
+
+    /// Reads the state of a verifier from a file, or starts a new state, if there is no file.
+    /// The state must belong to the same verifier `address`.
+    pub fn read(file: &str, address: &str, since: u64) -> Result<Self> {
+        if !Path::new(file).exists() {
+            return Ok(VerifierState::new(address, since));
+        }
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read the verifier state from '{}'", file))?;
+        let state: VerifierState = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid verifier state in '{}'", file))?;
+        if !state.address.eq_ignore_ascii_case(address) {
+            return Err(anyhow!(
+                "The verifier state in '{}' belongs to '{}', not to '{}'",
+                file,
+                state.address,
+                address
+            ));
+        }
+        Ok(state)
+    }
+
+    /// Writes the state of a verifier to a file.
+    pub fn write(&self, file: &str) -> Result<()> {
+        fs::write(file, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write the verifier state to '{}'", file))
+    }
+
+    /// The ids of the claims, which are accepted, but not settled yet
+    pub fn accepted(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .claims
+            .iter()
+            .filter(|(_, record)| record.verdict == Verdict::Accepted)
+            .map(|(id, _)| id.clone())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    /// The claims, which the checker has failed to check, with their ids
+    pub fn errored(&self) -> Vec<(String, SubmittedClaim)> {
+        let mut claims: Vec<(String, SubmittedClaim)> = self
+            .claims
+            .iter()
+            .filter(|(_, record)| record.verdict == Verdict::Errored)
+            .filter_map(|(id, record)| {
+                let claim = serde_json::from_value(record.claim.clone()?).ok()?;
+                Some((id.clone(), claim))
+            })
+            .collect();
+        claims.sort_by(|(a, _), (b, _)| a.cmp(b));
+        claims
+    }
+
+    /// Is the claim already processed
+    pub fn is_done(&self, id: &str) -> bool {
+        self.claims.get(id).is_some_and(ClaimRecord::is_done)
+    }
+
+    /// Checks the claim with the checker of the `options` and records the verdict. If the checker
+    /// fails, the claim is recorded with its body, so that it is checked again on the next poll.
+    pub fn check(
+        &mut self,
+        id: &str,
+        submitted: &SubmittedClaim,
+        options: &VerifierOptions,
+        now: u64,
+    ) -> Result<&ClaimRecord> {
+        let mut input = serde_json::to_value(submitted)?;
+        input["id"] = Value::String(id.to_string());
+        let record = match run_checker(&options.program, &input, options.program_timeout) {
+            Ok((true, _)) => ClaimRecord::new(Verdict::Accepted, now),
+            Ok((false, output)) => ClaimRecord {
+                reason: Some(output),
+                ..ClaimRecord::new(Verdict::Rejected, now)
+            },
+            Err(err) => ClaimRecord {
+                reason: Some(format!("{:#}", err)),
+                claim: Some(serde_json::to_value(submitted)?),
+                ..ClaimRecord::new(Verdict::Errored, now)
+            },
+        };
+        self.claims.insert(id.to_string(), record);
+        Ok(&self.claims[id])
+    }
+}
+
+/// The default state file of a verifier: `<name>.verifier.<account>.json` next to the config
+/// file of the `name`, or in the current directory for a config without a file.
+pub fn state_file(config_name: &str, config_file: Option<&Path>, account: &str) -> String {
+    let file = format!("{}.verifier.{}.json", config_name, account);
+    match config_file.and_then(Path::parent) {
+        Some(dir) => dir.join(file).to_string_lossy().to_string(),
+        None => file,
+    }
+}
+
+/// Finds the submitted claims in the response of `vsl_listSubmittedClaimsForReceiver`,
+/// with their ids. A claim may be wrapped into a signed or a timestamped message.
+pub fn submitted_claims(list: &Value) -> Vec<(String, SubmittedClaim)> {
+    list.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(find_submitted_claim)
+        .map(|submitted| {
+            let id = VerifiedClaim::claim_id_hash(
+                &submitted.from.address,
+                &submitted.nonce,
+                &submitted.claim,
+            )
+            .to_string();
+            (id, submitted)
+        })
+        .collect()
+}
+
+fn find_submitted_claim(value: &Value) -> Option<SubmittedClaim> {
+    match value {
+        Value::Object(map) => serde_json::from_value(value.clone())
+            .ok()
+            .or_else(|| map.values().find_map(find_submitted_claim)),
+        _ => None,
+    }
+}
+
+/// Runs the checker `program` with a shell. The `input` is written to the standard input
+/// of the checker. The exit code 0 accepts the claim, 1 rejects it, and any other code is
+/// a failure of the checker. Returns the verdict and the output of the checker.
+pub fn run_checker(program: &str, input: &Value, timeout: u64) -> Result<(bool, String)> {
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    let mut child = Command::new(shell)
+        .args([flag, program])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to launch the checker '{}'", program))?;
+    // The pipes are served by threads, so that a large input, or output, does not block the checker
+    let mut stdin = child.stdin.take().unwrap();
+    let input = input.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let mut stdout = child.stdout.take().unwrap();
+    let reader = std::thread::spawn(move || {
+        let mut output = String::new();
+        stdout.read_to_string(&mut output).map(|_| output)
+    });
+    let mut stderr = child.stderr.take().unwrap();
+    let error_reader = std::thread::spawn(move || {
+        let mut output = String::new();
+        stderr.read_to_string(&mut output).map(|_| output)
+    });
+    let deadline = Instant::now() + Duration::from_secs(timeout);
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            child.kill()?;
+            child.wait()?;
+            return Err(anyhow!(
+                "The checker '{}' is killed after {} seconds",
+                program,
+                timeout
+            ));
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    };
+    // The checker may exit without reading its input
+    let _ = writer.join();
+    let stdout = reader.join().unwrap_or(Ok(String::new()))?;
+    let stderr = error_reader.join().unwrap_or(Ok(String::new()))?;
+    let output = match stdout.trim() {
+        "" => stderr.trim().to_string(),
+        stdout => stdout.to_string(),
+    };
+    match status.code() {
+        Some(0) => Ok((true, output)),
+        Some(1) => Ok((false, output)),
+        _ => Err(anyhow!(
+            "The checker '{}' failed with {}: {}",
+            program,
+            status,
+            output
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_verifier_state() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("state.json").to_string_lossy().to_string();
+        let mut state = VerifierState::read(&file, "0xAB", 10).unwrap();
+        assert_eq!(state, VerifierState::new("0xab", 10));
+        state
+            .claims
+            .insert("0x01".to_string(), ClaimRecord::new(Verdict::Accepted, 20));
+        state
+            .claims
+            .insert("0x02".to_string(), ClaimRecord::new(Verdict::Rejected, 20));
+        state.write(&file).unwrap();
+        let state = VerifierState::read(&file, "0xab", 30).unwrap();
+        assert_eq!(state.since, 10);
+        assert!(!state.is_done("0x01"));
+        assert!(state.is_done("0x02"));
+        assert!(!state.is_done("0x03"));
+        assert_eq!(state.accepted(), vec!["0x01"]);
+        assert!(VerifierState::read(&file, "0xcd", 30).is_err());
+    }
+
+    #[test]
+    fn test_state_file() {
+        assert_eq!(state_file("tmp", None, "acc1"), "tmp.verifier.acc1.json");
+        assert_eq!(
+            state_file("main", Some(Path::new("/configs/main.json")), "acc1"),
+            "/configs/main.verifier.acc1.json"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_retry() {
+        use std::str::FromStr as _;
+        use vsl_sdk::Timestamp;
+        use vsl_sdk::rpc_messages::VslAddress;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let marker = dir.path().join("failed");
+        let file = dir.path().join("state.json").to_string_lossy().to_string();
+        // The checker fails the first time, and accepts the claim then
+        let options = VerifierOptions {
+            program: format!(
+                "test -f '{0}' && exit 0; touch '{0}'; exit 2",
+                marker.display()
+            ),
+            program_timeout: 10,
+            since: None,
+            state_file: file.clone(),
+        };
+        let submitted = SubmittedClaim {
+            claim: "AAA".to_string(),
+            claim_type: "test".to_string(),
+            proof: String::new(),
+            nonce: "0".to_string(),
+            to: Vec::new(),
+            quorum: 1,
+            from: VslAddress::from_str("0x749ab3318b74907f6e5856ce9ce1f3b55e3bb38a").unwrap(),
+            expires: Timestamp::from_seconds(2_000_000_000),
+            fee: "0x1".to_string(),
+        };
+        let mut state = VerifierState::new("0xab", 10);
+        let record = state.check("0x01", &submitted, &options, 20).unwrap();
+        assert_eq!(record.verdict, Verdict::Errored);
+        assert!(!state.is_done("0x01"));
+        state.write(&file).unwrap();
+        // The failed claim is kept with its body, so it is checked again out of the poll window
+        let mut state = VerifierState::read(&file, "0xab", 30).unwrap();
+        let errored = state.errored();
+        assert_eq!(errored.len(), 1);
+        let (id, claim) = &errored[0];
+        assert_eq!(id, "0x01");
+        let record = state.check(id, claim, &options, 40).unwrap();
+        assert_eq!(record.verdict, Verdict::Accepted);
+        assert_eq!(record.claim, None);
+        assert!(state.errored().is_empty());
+        assert_eq!(state.accepted(), vec!["0x01"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_checker() {
+        let input = json!({ "claim": "AAA" });
+        assert_eq!(
+            run_checker("grep -q AAA && echo ok", &input, 10).unwrap(),
+            (true, "ok".to_string())
+        );
+        assert_eq!(
+            run_checker("echo 'bad claim' >&2; exit 1", &input, 10).unwrap(),
+            (false, "bad claim".to_string())
+        );
+        assert!(run_checker("exit 2", &input, 10).is_err());
+        assert!(run_checker("sleep 5", &input, 1).is_err());
+    }
+
+    #[test]
+    fn test_submitted_claims() {
+        assert!(submitted_claims(&json!([{ "claim": 1 }, "text"])).is_empty());
+        assert!(submitted_claims(&json!({})).is_empty());
+    }
+}
@@ -2,9 +2,12 @@
 
 use std::str::FromStr as _;
 use vsl_cli::commands::Commands;
+use vsl_cli::commands::SignOnlyArgs;
 use vsl_cli::configs::CliMode;
 use vsl_cli::configs::Configs;
 use vsl_cli::execute::execute_command;
+use vsl_cli::inputs::DEFAULT_MAX_INPUT_SIZE;
+use vsl_cli::inputs::Encoding;
 use vsl_cli::rpc_client::RpcClient;
 use vsl_sdk::Address;
 use vsl_sdk::rpc_messages::IdentifiableClaim as _;
@@ -21,19 +24,25 @@ fn test_endpoints() -> anyhow::Result<()> {
         claim_type: "logical".to_string(),
         proof: "Obvious".to_string(),
         expires: None,
-        lifetime: Some(3600),
+        lifetime: Some("1h".to_string()),
         fee: "1".to_string(),
+        claim_encoding: Encoding::Raw,
+        proof_encoding: Encoding::Raw,
+        max_size: DEFAULT_MAX_INPUT_SIZE,
+        signing: SignOnlyArgs::default(),
     });
     exec_command(Commands::ClaimSettle {
         network: None,
         claim: "***All men are liars".to_string(),
         address: Some(String::new()),
+        signing: SignOnlyArgs::default(),
     });
     exec_command(Commands::ClaimSettled {
         network: None,
         address: Some(CLIENT.to_string()),
         since: None,
-        within: Some(3600),
+        within: Some("1h".to_string()),
+        raw: false,
     });
     exec_command(Commands::ClaimGet {
         network: None,
@@ -43,6 +52,7 @@ fn test_endpoints() -> anyhow::Result<()> {
             "***All men are liars",
         )
         .to_string(),
+        raw: false,
     });
     Ok(())
 }
@@ -32,6 +32,7 @@ fn test_cli_end_to_end_batch() {
     let output = Command::new("cargo")
         .env("RUST_LOG", "info")
         .env("VSL_CLI_ERROR_PREFIX", error_prefix)
+        .env("VSL_CLI_PASSWORD", "vsl-cli-test")
         .args(args)
         .stdin(batch_file)
         .output()
@@ -70,14 +71,15 @@ fn test_cli_end_to_end_batch() {
             errors.push(err);
         }
     }
-    for line in stdout.split("\n") {
+    // The errors are printed to stderr
+    for line in stderr.split("\n") {
         if err_predicate(line) {
             println!("Error: {}", line);
             errors.push(line.to_string());
         }
     }
     if errors.len() > 0 {
-        panic!("FAILED:\n{}\n{}", errors.join("\n"), stderr)
+        panic!("FAILED:\n{}", errors.join("\n"))
     } else {
         print!("PASSED\n")
     }
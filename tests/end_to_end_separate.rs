@@ -29,6 +29,7 @@ fn test_cli_end_to_end_separate() {
         let output = Command::new("cargo")
             .env("RUST_LOG", "info")
             .env("VSL_CLI_ERROR_PREFIX", error_prefix)
+            .env("VSL_CLI_PASSWORD", "vsl-cli-test")
             .args(args)
             .output()
             .expect("failed to execute CLI batch file");
@@ -36,24 +37,26 @@ fn test_cli_end_to_end_separate() {
         let stderr = String::from_utf8(output.stderr).unwrap();
         print!("{}", stdout);
 
-        if !output.status.success() {
-            eprintln!(
-                "command: '{}', cli stdout:\n{}, stderr:\n{}",
-                line, stdout, stderr
-            );
-            panic!("`vsl-cli` process failed with status: {}", output.status);
-        }
-
         let err_predicate = |line: &str| {
             line.contains(error_prefix) && !line.contains("Endpoint not yet implemented")
         };
 
-        for line in stdout.split("\n") {
+        // The errors are printed to stderr, the failed command exits with a non-zero status
+        let mut failed = false;
+        for line in stderr.split("\n") {
             if err_predicate(line) {
                 println!("Error: {}", line);
                 errors.push(line.to_string());
+                failed = true;
             }
         }
+        if !output.status.success() && !failed && !stderr.contains("Endpoint not yet implemented") {
+            eprintln!(
+                "command: '{}', cli stdout:\n{}, stderr:\n{}",
+                line, stdout, stderr
+            );
+            panic!("`vsl-cli` process failed with status: {}", output.status);
+        }
     }
 
     if errors.len() > 0 {
@@ -0,0 +1,76 @@
+#![allow(unused)]
+
+use alloy::primitives::Address;
+use alloy::primitives::B256;
+use alloy::signers::SignerSync;
+use alloy::signers::local::PrivateKeySigner;
+use jsonrpsee::RpcModule;
+use jsonrpsee::server::Server;
+use jsonrpsee::server::ServerHandle;
+use jsonrpsee::types::ErrorObjectOwned;
+use serde_json::Value;
+use std::str::FromStr as _;
+use tokio::runtime::Runtime;
+use vsl_cli::signers::AccountSigner;
+use vsl_cli::signers::REMOTE_SIGNER_METHOD;
+use vsl_cli::signers::RemoteSigner;
+
+const PRIVATE_KEY: &str = "b6dd863bea551b5bb27ce9917316a01ea4c331f24e0e4fe56e28eb430f175ed7";
+
+/// The stand-in of a signing service: signs any requested hash with `signer`.
+fn start_signer_server(runtime: &Runtime, signer: PrivateKeySigner) -> (String, ServerHandle) {
+    runtime.block_on(async {
+        let server = Server::builder()
+            .build("127.0.0.1:0")
+            .await
+            .expect("failed to start the signer server");
+        let url = format!("http://{}", server.local_addr().unwrap());
+        let mut module = RpcModule::new(signer);
+        module
+            .register_method(REMOTE_SIGNER_METHOD, |params, signer, _| {
+                let request: Value = params.parse()?;
+                let hash = request
+                    .get("hash")
+                    .and_then(|hash| hash.as_str())
+                    .and_then(|hash| B256::from_str(hash).ok())
+                    .ok_or(ErrorObjectOwned::owned::<()>(
+                        -32602,
+                        "`hash` is absent",
+                        None,
+                    ))?;
+                signer
+                    .sign_hash_sync(&hash)
+                    .map(|signature| signature.to_string())
+                    .map_err(|err| ErrorObjectOwned::owned::<()>(-32000, err.to_string(), None))
+            })
+            .expect("failed to register the signer method");
+        (url, server.start(module))
+    })
+}
+
+#[test]
+fn test_remote_signer() {
+    let runtime = Runtime::new().unwrap();
+    let local = PrivateKeySigner::from_str(PRIVATE_KEY).unwrap();
+    let (url, handle) = start_signer_server(&runtime, local.clone());
+    let hash = B256::repeat_byte(0x17);
+
+    let remote = AccountSigner::Remote(RemoteSigner {
+        url: url.clone(),
+        address: local.address(),
+    });
+    assert_eq!(remote.address(), local.address());
+    assert_eq!(
+        remote.sign_hash_sync(&hash).expect("must sign"),
+        local.sign_hash_sync(&hash).unwrap()
+    );
+
+    // The signature of another key must be rejected
+    let impostor = RemoteSigner {
+        url: url,
+        address: Address::repeat_byte(0x01),
+    };
+    assert!(impostor.sign_hash_sync(&hash).is_err());
+
+    handle.stop().unwrap();
+}